@@ -0,0 +1,18 @@
+use discounted_zeroone_knapsack::{Item, Problem};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+// uncorrelated D{0-1}KP instance: the third item of a group discounts the first two together
+pub fn make_instance(groups: usize, seed: u64) -> Problem {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut total_cost = 0;
+    let data: Vec<Vec<Item>> = (0..groups).map(|_| {
+        let (c1, c2) = (rng.gen_range(10..100), rng.gen_range(10..100));
+        let (g1, g2) = (rng.gen_range(10..100), rng.gen_range(10..100));
+        let (c1, c2, g1, g2) = if c1 < c2 { (c1, c2, g1, g2) } else { (c2, c1 + 1, g2, g1) };
+        let c3 = rng.gen_range(c2 + 1..c1 + c2);
+        total_cost += c3;
+        vec![Item::new(g1, c1), Item::new(g2, c2), Item::new(g1 + g2, c3)]
+    }).collect();
+    Problem::new((total_cost / 2) as u32, data)
+}
@@ -0,0 +1,32 @@
+//! Evolution of the genetic algorithm on large synthetic instances, the seeding left out.
+//!
+//! ```shell
+//! cargo bench --bench genetic
+//! ```
+
+mod common;
+
+use common::make_instance;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use discounted_zeroone_knapsack::{ConstraintHandling, GeneticAlgorithm, SolverBuilder};
+
+fn genetic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("genetic");
+    group.sample_size(10);
+    for groups in [1000, 3000, 10000] {
+        let problem = make_instance(groups, 42);
+        for handling in [ConstraintHandling::Repair, ConstraintHandling::AdaptivePenalty] {
+            let builder = SolverBuilder::new().problem(problem.clone())
+                .population_size(100)
+                .stagnation_limit(20)
+                .constraint_handling(handling);
+            group.bench_with_input(BenchmarkId::new(format!("run_{}", handling), groups), &builder, |b, builder| {
+                b.iter_batched(|| builder.clone().build().unwrap(), |mut solver| solver.run(), BatchSize::LargeInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, genetic);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+//! Preprocessing of large synthetic instances, dominated by the UB-fix.
+//!
+//! ```shell
+//! cargo bench --bench preprocessing
+//! ```
+
+mod common;
+
+use common::make_instance;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use discounted_zeroone_knapsack::ProblemPreprocessor;
+
+fn preprocessing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("preprocessing");
+    group.sample_size(10);
+    for groups in [1000, 3000, 10000] {
+        let problem = make_instance(groups, 42);
+        group.bench_with_input(BenchmarkId::new("process_problem", groups), &problem, |b, problem| {
+            b.iter(|| ProblemPreprocessor::new(problem).process_problem())
+        });
+        group.bench_with_input(BenchmarkId::new("dantzig_bound", groups), &problem, |b, problem| {
+            b.iter(|| ProblemPreprocessor::new(problem).dantzig_bound())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, preprocessing);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Builds a small instance in code, runs the preprocessing and the genetic algorithm, then
+//! prints the choice taken for every group.
+//!
+//! ```shell
+//! cargo run --example solve
+//! ```
+
+use discounted_zeroone_knapsack::{GeneticAlgorithm, Item, PopulationSize, Problem, ProblemPreprocessor, SolverBuilder};
+
+fn main() {
+    let problem = Problem::new(60, vec![
+        vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+        vec![Item::new(15, 7), Item::new(25, 12), Item::new(40, 15)],
+        vec![Item::new(20, 1), Item::new(35, 35), Item::new(55, 35)],
+        vec![Item::new(11, 3), Item::new(22, 15), Item::new(33, 16)],
+    ]);
+
+    let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+    println!("LP upper bound: {}", preprocessing.relaxation_result.v_up());
+
+    let mut executor = SolverBuilder::new()
+        .problem(problem)
+        .seed(42)
+        .population_size(PopulationSize::PerGroup(5.0))
+        .enhanced_seeding(true)
+        .preprocessing_result(preprocessing)
+        .build()
+        .unwrap_or_else(|why| panic!("invalid configuration: {}", why));
+    let solution = executor.run();
+
+    println!("best: {} cost: {} generations: {}", solution.fitness(), solution.cost(), solution.generations());
+    for (group, choice) in solution.choices().iter().enumerate() {
+        match choice {
+            0 => println!("group {}: empty", group),
+            k => println!("group {}: item {}", group, k),
+        }
+    }
+}
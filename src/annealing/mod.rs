@@ -0,0 +1,358 @@
+use crate::error::SolverError;
+use crate::genetic::operators;
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use crate::utils;
+use log::{debug, info};
+use rand::rngs::SmallRng;
+use rand::Rng;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Temperature update applied at the end of every epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cooling {
+    /// `T <- alpha·T`
+    Geometric(f64),
+    /// `T <- T - step·T0`, stopping at zero
+    Linear(f64),
+    /// Lundy and Mees, `T <- T / (1 + beta·T)`
+    Lundy(f64),
+}
+
+impl Cooling {
+    fn cool(&self, temperature: f64, initial_temperature: f64) -> f64 {
+        match self {
+            Cooling::Geometric(alpha) => alpha * temperature,
+            Cooling::Linear(step) => (temperature - step * initial_temperature).max(0.0),
+            Cooling::Lundy(beta) => temperature / (1.0 + beta * temperature),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Cooling::Geometric(alpha) if !(*alpha > 0.0 && *alpha < 1.0) => Err(format!("geometric cooling factor {} must be in (0, 1)", alpha)),
+            Cooling::Linear(step) if !(*step > 0.0 && *step <= 1.0) => Err(format!("linear cooling step {} must be in (0, 1]", step)),
+            Cooling::Lundy(beta) if !(*beta > 0.0 && beta.is_finite()) => Err(format!("Lundy cooling factor {} must be positive", beta)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Cooling {
+    type Err = String;
+
+    // "geometric:0.95", "linear:0.01" or "lundy:0.001"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.trim().split_once(':')
+            .ok_or_else(|| format!("invalid cooling '{}', expected e.g. geometric:0.95", s))?;
+        let value = value.trim().parse::<f64>().map_err(|_| format!("invalid cooling parameter in '{}'", s))?;
+        let cooling = match name.trim() {
+            "geometric" => Cooling::Geometric(value),
+            "linear" => Cooling::Linear(value),
+            "lundy" => Cooling::Lundy(value),
+            other => return Err(format!("unknown cooling '{}', expected geometric, linear or lundy", other)),
+        };
+        cooling.validate()?;
+        Ok(cooling)
+    }
+}
+
+/// Cooling schedule of the simulated annealing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnealingSchedule {
+    pub(crate) cooling: Cooling,
+    pub(crate) initial_temperature: Option<f64>,
+    pub(crate) initial_acceptance: f64,
+    pub(crate) epoch_length: Option<usize>,
+    pub(crate) reheat_interval: u32,
+    pub(crate) reheat_ratio: f64,
+}
+
+impl AnnealingSchedule {
+    pub fn new() -> Self {
+        AnnealingSchedule::default()
+    }
+
+    pub fn cooling(mut self, cooling: Cooling) -> Self {
+        self.cooling = cooling;
+        self
+    }
+
+    /// Fixed initial temperature, calibrated from sampled moves when not given.
+    pub fn initial_temperature(mut self, temperature: f64) -> Self {
+        self.initial_temperature = Some(temperature);
+        self
+    }
+
+    /// Probability of accepting an average worsening move at the calibrated initial temperature.
+    pub fn initial_acceptance(mut self, acceptance: f64) -> Self {
+        self.initial_acceptance = acceptance;
+        self
+    }
+
+    /// Moves tried at each temperature, the number of groups by default.
+    pub fn epoch_length(mut self, epoch_length: usize) -> Self {
+        self.epoch_length = Some(epoch_length);
+        self
+    }
+
+    /// Raises the temperature to `ratio·T0` after every `interval` epochs without improvement,
+    /// an interval of 0 disables reheating.
+    pub fn reheating(mut self, interval: u32, ratio: f64) -> Self {
+        self.reheat_interval = interval;
+        self.reheat_ratio = ratio;
+        self
+    }
+
+    fn validate(&self) -> Result<(), SolverError> {
+        self.cooling.validate().map_err(SolverError::InvalidAnnealingSchedule)?;
+        if let Some(temperature) = self.initial_temperature {
+            if !(temperature > 0.0 && temperature.is_finite()) {
+                return Err(SolverError::InvalidAnnealingSchedule(format!("initial temperature {} must be positive", temperature)));
+            }
+        }
+        if !(self.initial_acceptance > 0.0 && self.initial_acceptance < 1.0) {
+            return Err(SolverError::InvalidAnnealingSchedule(format!("initial acceptance {} must be in (0, 1)", self.initial_acceptance)));
+        }
+        if self.epoch_length == Some(0) {
+            return Err(SolverError::InvalidAnnealingSchedule("epoch length must be greater than 0".to_string()));
+        }
+        if self.reheat_interval > 0 && !(self.reheat_ratio > 0.0 && self.reheat_ratio.is_finite()) {
+            return Err(SolverError::InvalidAnnealingSchedule(format!("reheat ratio {} must be positive", self.reheat_ratio)));
+        }
+        Ok(())
+    }
+}
+
+impl Default for AnnealingSchedule {
+    fn default() -> Self {
+        AnnealingSchedule {
+            cooling: Cooling::Geometric(0.95),
+            initial_temperature: None,
+            initial_acceptance: 0.8,
+            epoch_length: None,
+            reheat_interval: 0,
+            reheat_ratio: 0.5,
+        }
+    }
+}
+
+/// Single-solution simulated annealing.
+///
+/// Moves are the mutation of the genetic algorithm, setting one group to a random option, and
+/// swaps of the options of two groups, both followed by the repair rule of the genetic algorithm.
+/// The search stops after `stagnation_limit` epochs without improvement of the best solution.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{AnnealingSchedule, Cooling, Item, Problem, SimulatedAnnealing};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let schedule = AnnealingSchedule::new().cooling(Cooling::Geometric(0.9)).reheating(5, 0.5);
+/// let (solution, _) = SimulatedAnnealing::new(problem).schedule(schedule).seed(3).solve().unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// ```
+pub struct SimulatedAnnealing {
+    problem: Arc<Problem>,
+    schedule: AnnealingSchedule,
+    seed: u64,
+    stagnation_limit: u8,
+    cancellation: Cancellation,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnnealingStatistics {
+    pub(crate) epochs: u32,
+    pub(crate) moves: u64,
+    pub(crate) accepted: u64,
+    pub(crate) reheats: u32,
+    pub(crate) initial_temperature: f64,
+}
+
+impl AnnealingStatistics {
+    pub fn epochs(&self) -> u32 {
+        self.epochs
+    }
+
+    pub fn moves(&self) -> u64 {
+        self.moves
+    }
+
+    pub fn accepted(&self) -> u64 {
+        self.accepted
+    }
+
+    pub fn reheats(&self) -> u32 {
+        self.reheats
+    }
+
+    pub fn initial_temperature(&self) -> f64 {
+        self.initial_temperature
+    }
+}
+
+impl SimulatedAnnealing {
+    pub fn new(problem: impl Into<Arc<Problem>>) -> Self {
+        SimulatedAnnealing {
+            problem: problem.into(),
+            schedule: AnnealingSchedule::default(),
+            seed: 0,
+            stagnation_limit: 128,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    pub fn schedule(mut self, schedule: AnnealingSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Epochs without improvement before the search stops.
+    pub fn stagnation_limit(mut self, stagnation_limit: u8) -> Self {
+        self.stagnation_limit = stagnation_limit;
+        self
+    }
+
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    // one of the two moves followed by the repair rule
+    fn neighbour(&self, rng: &mut SmallRng, genes: &[usize]) -> Vec<usize> {
+        let mut neighbour = genes.to_vec();
+        if rng.gen_bool(0.5) {
+            operators::change_option(rng, &mut neighbour);
+        } else {
+            operators::swap_groups(rng, &mut neighbour);
+        }
+        operators::repair(&self.problem, &mut neighbour);
+        neighbour
+    }
+
+    // temperature at which an average worsening move is accepted with the target probability
+    fn calibrate(&self, rng: &mut SmallRng, genes: &[usize], gain: i64) -> f64 {
+        let worsening: Vec<f64> = (0..100)
+            .map(|_| operators::summary(&self.problem, &self.neighbour(rng, genes)).0 - gain)
+            .filter(|delta| *delta < 0)
+            .map(|delta| -delta as f64)
+            .collect();
+        if worsening.is_empty() {
+            return 1.0;
+        }
+        let mean = worsening.iter().sum::<f64>() / worsening.len() as f64;
+        -mean / self.schedule.initial_acceptance.ln()
+    }
+
+    pub fn solve(&self) -> Result<(Solution, AnnealingStatistics), SolverError> {
+        self.schedule.validate()?;
+        if self.problem.data.is_empty() {
+            return Err(SolverError::EmptyProblem);
+        }
+        info!("Running simulated annealing for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
+
+        let mut rng = utils::make_rng(self.seed);
+        let mut genes: Vec<usize> = (0..self.problem.data.len()).map(|_| rng.gen_range(0..4)).collect();
+        operators::repair(&self.problem, &mut genes);
+        let mut gain = operators::summary(&self.problem, &genes).0;
+        let (mut best_genes, mut best) = (genes.clone(), gain);
+
+        let initial_temperature = match self.schedule.initial_temperature {
+            Some(temperature) => temperature,
+            None => self.calibrate(&mut rng, &genes, gain),
+        };
+        let epoch_length = self.schedule.epoch_length.unwrap_or(self.problem.data.len());
+        debug!("Simulated annealing initial temperature: {} epoch length: {}", initial_temperature, epoch_length);
+
+        let mut temperature = initial_temperature;
+        let mut statistics = AnnealingStatistics { epochs: 0, moves: 0, accepted: 0, reheats: 0, initial_temperature };
+        let mut stagnant: u32 = 0;
+        while stagnant <= self.stagnation_limit as u32 && !self.cancellation.is_cancelled() {
+            let mut improved = false;
+            for _ in 0..epoch_length {
+                let neighbour = self.neighbour(&mut rng, &genes);
+                let neighbour_gain = operators::summary(&self.problem, &neighbour).0;
+                let delta = neighbour_gain - gain;
+                statistics.moves += 1;
+                if delta >= 0 || (temperature > 0.0 && rng.gen::<f64>() < (delta as f64 / temperature).exp()) {
+                    genes = neighbour;
+                    gain = neighbour_gain;
+                    statistics.accepted += 1;
+                    if gain > best {
+                        best = gain;
+                        best_genes = genes.clone();
+                        improved = true;
+                    }
+                }
+            }
+            statistics.epochs += 1;
+            stagnant = if improved { 0 } else { stagnant + 1 };
+            temperature = self.schedule.cooling.cool(temperature, initial_temperature);
+            if self.schedule.reheat_interval > 0 && stagnant > 0 && stagnant % self.schedule.reheat_interval == 0 {
+                temperature = self.schedule.reheat_ratio * initial_temperature;
+                statistics.reheats += 1;
+                // restart from the best solution at the raised temperature
+                genes = best_genes.clone();
+                gain = best;
+            }
+            info!("Annealing epoch: {} temperature: {:.4} current: {} best: {}", statistics.epochs, temperature, gain, best);
+        }
+
+        debug!("Simulated annealing accepted {} of {} moves with {} reheats", statistics.accepted, statistics.moves, statistics.reheats);
+        Ok((Solution::from_choices(&self.problem, best_genes, statistics.epochs), statistics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::make_thirty_groups;
+
+    #[test]
+    fn test_cooling_from_str() {
+        assert_eq!(Cooling::from_str("geometric:0.9"), Ok(Cooling::Geometric(0.9)));
+        assert_eq!(Cooling::from_str("lundy:0.01"), Ok(Cooling::Lundy(0.01)));
+        assert!(Cooling::from_str("geometric:1.5").is_err());
+        assert!(Cooling::from_str("cubic:0.5").is_err());
+    }
+
+    #[test]
+    fn test_calibration_accepts_worsening_moves() {
+        let (_, statistics) = SimulatedAnnealing::new(make_thirty_groups()).seed(1).solve().unwrap();
+        assert!(statistics.initial_temperature() > 0.0);
+        assert!(statistics.accepted() < statistics.moves());
+
+        let schedule = AnnealingSchedule::new().initial_temperature(20.0);
+        let (_, statistics) = SimulatedAnnealing::new(make_thirty_groups()).schedule(schedule).seed(1).solve().unwrap();
+        assert_eq!(statistics.initial_temperature(), 20.0);
+    }
+
+    #[test]
+    fn test_reheating_after_stagnation() {
+        let schedule = AnnealingSchedule::new().cooling(Cooling::Lundy(0.05)).reheating(10, 0.3);
+        let (solution, statistics) = SimulatedAnnealing::new(make_thirty_groups()).schedule(schedule).seed(1).solve().unwrap();
+        assert!(solution.cost() <= 300);
+        assert_eq!(solution.generations(), statistics.epochs());
+        // the search stops after 128 stagnant epochs, a reheat every 10 of them
+        assert!(statistics.reheats() >= 12);
+
+        let (_, statistics) = SimulatedAnnealing::new(make_thirty_groups()).seed(1).solve().unwrap();
+        assert_eq!(statistics.reheats(), 0);
+    }
+
+    #[test]
+    fn test_solve_rejects_invalid_schedule() {
+        let schedule = AnnealingSchedule::new().initial_acceptance(1.0);
+        let result = SimulatedAnnealing::new(make_thirty_groups()).schedule(schedule).solve();
+        assert!(matches!(result, Err(SolverError::InvalidAnnealingSchedule(_))));
+    }
+}
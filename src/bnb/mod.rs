@@ -0,0 +1,467 @@
+use crate::error::SolverError;
+use crate::preprocessing::{PreprocessingResult, ProblemPreprocessor};
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use log::{debug, info};
+use std::time::Duration;
+use std::sync::Arc;
+
+/// Depth-first branch-and-bound, fixing the choice of one group at each level.
+///
+/// Nodes are pruned with the LP relaxation computed over the relaxed items of the preprocessing,
+/// restricted to the groups still free. Groups in `f_1` are fixed to their third item, the
+/// LP-dominated items in `f_0` are only tried last: LP dominance does not rule an item out of the
+/// integer optimum. The bound needs the items of a group ordered by cost, groups given in another
+/// order are sorted for the search and the choices are returned in the input order.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{BranchAndBoundSolver, Item, Problem};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let (solution, statistics) = BranchAndBoundSolver::new(problem).solve().unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// assert!(statistics.proven_optimal());
+/// ```
+pub struct BranchAndBoundSolver {
+    problem: Arc<Problem>,
+    preprocessing_result: Option<PreprocessingResult>,
+    incumbent: Option<Vec<usize>>,
+    cancellation: Cancellation,
+}
+
+#[derive(Debug, Clone)]
+pub struct BranchAndBoundStatistics {
+    pub(crate) nodes: u64,
+    pub(crate) upper_bound: f64,
+    pub(crate) proven_optimal: bool,
+    pub(crate) best: i64,
+}
+
+impl BranchAndBoundStatistics {
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Best bound proven on the optimum, equal to the best value when the search completed.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+
+    pub fn proven_optimal(&self) -> bool {
+        self.proven_optimal
+    }
+
+    /// Relative gap between the proven bound and the best value found.
+    pub fn gap(&self) -> f64 {
+        if self.upper_bound <= 0.0 {
+            return 0.0;
+        }
+        ((self.upper_bound - self.best as f64) / self.upper_bound).max(0.0)
+    }
+}
+
+// LP increment of a group, taken whole or in part by the bound
+struct Increment {
+    group: usize,
+    gain: i64,
+    cost: i64,
+}
+
+struct Search<'a> {
+    problem: &'a Problem,
+    increments: Vec<Increment>,
+    // branching order of the groups and the depth at which every group is fixed
+    order: Vec<usize>,
+    depth_of: Vec<usize>,
+    options: Vec<Vec<usize>>,
+    choices: Vec<usize>,
+    best_choices: Vec<usize>,
+    best: i64,
+    nodes: u64,
+    open_bound: f64,
+    cancellation: &'a Cancellation,
+    stopped: bool,
+}
+
+impl BranchAndBoundSolver {
+    pub fn new(problem: impl Into<Arc<Problem>>) -> Self {
+        BranchAndBoundSolver {
+            problem: problem.into(),
+            preprocessing_result: None,
+            incumbent: None,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    /// Preprocessing to use, it is computed by the solver when not given.
+    pub fn preprocessing_result(mut self, preprocessing_result: PreprocessingResult) -> Self {
+        self.preprocessing_result = Some(preprocessing_result);
+        self
+    }
+
+    /// Choices of a known solution, e.g. the best chromosome of the genetic algorithm.
+    pub fn incumbent(mut self, choices: Vec<usize>) -> Self {
+        self.incumbent = Some(choices);
+        self
+    }
+
+    pub fn time_limit(mut self, time_limit: Duration) -> Self {
+        self.cancellation = self.cancellation.time_limit(time_limit);
+        self
+    }
+
+    /// Stops the search with the best solution found so far when `cancellation` is triggered.
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    fn validate(&self) -> Result<(), SolverError> {
+        if let Some(group) = self.problem.data.iter().position(|group| group.len() != 3) {
+            return Err(SolverError::InvalidGroup { group, items: self.problem.data[group].len() });
+        }
+        let Some(incumbent) = &self.incumbent else {
+            return Ok(());
+        };
+        if incumbent.len() != self.problem.data.len() {
+            return Err(SolverError::InvalidIncumbent(format!(
+                "{} choices given for {} groups", incumbent.len(), self.problem.data.len()
+            )));
+        }
+        match incumbent.iter().position(|choice| *choice > 3) {
+            Some(group) => Err(SolverError::InvalidIncumbent(format!(
+                "choice {} of group {} is not between 0 and 3", incumbent[group], group
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    // the dominance rules of the preprocessing, hence the bound, expect the items of a group
+    // ordered by cost, sorted[i][k] is the index in the input of the k-th cheapest item of group i
+    fn sorted_items(&self) -> Vec<[usize; 3]> {
+        self.problem.data.iter().map(|group| {
+            let mut sorted = [0, 1, 2];
+            sorted.sort_by_key(|k| (group[*k].cost, group[*k].gain));
+            sorted
+        }).collect()
+    }
+
+    fn evaluate(problem: &Problem, choices: &[usize]) -> Option<i64> {
+        let (mut gain, mut cost) = (0, 0);
+        for (i, choice) in choices.iter().enumerate() {
+            if *choice != 0 {
+                gain += problem.data[i][*choice - 1].gain;
+                cost += problem.data[i][*choice - 1].cost;
+            }
+        }
+        (cost <= problem.capacity as i64).then_some(gain)
+    }
+
+    fn choices_of(x: &[[f64; 3]]) -> Vec<usize> {
+        x.iter().map(|x_i| x_i.iter().position(|v| *v == 1.0).map_or(0, |k| k + 1)).collect()
+    }
+
+    pub fn solve(&self) -> Result<(Solution, BranchAndBoundStatistics), SolverError> {
+        self.validate()?;
+        info!("Running branch and bound for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
+        let sorted = self.sorted_items();
+        let is_ordered = sorted.iter().all(|items| *items == [0, 1, 2]);
+        // a given preprocessing refers to the input order, it is only reused when nothing moves
+        let reordered = (!is_ordered).then(|| Problem::with_size(
+            self.problem.capacity,
+            self.problem.data.iter().zip(&sorted).map(|(group, items)| items.iter().map(|k| group[*k].clone()).collect()).collect(),
+            self.problem.size,
+        ));
+        let problem = reordered.as_ref().unwrap_or(&self.problem);
+        let preprocessing_result = match &self.preprocessing_result {
+            Some(result) if is_ordered && !result.relaxation_result.relaxed.is_empty() => result.clone(),
+            _ => ProblemPreprocessor::new(problem).process_problem(),
+        };
+        let m = problem.data.len();
+
+        let mut increments: Vec<Increment> = preprocessing_result.relaxation_result.relaxed.iter()
+            .flat_map(|group| group.iter())
+            .filter(|item| item.ratio > 0.0)
+            .map(|item| Increment { group: item.set_index, gain: item.gain, cost: item.cost })
+            .collect();
+        increments.sort_by(|a, b| (b.gain as f64 / b.cost as f64).total_cmp(&(a.gain as f64 / a.cost as f64)));
+
+        // groups whose best increment comes first are fixed first
+        let mut order: Vec<usize> = Vec::with_capacity(m);
+        let mut depth_of = vec![usize::MAX; m];
+        for increment in &increments {
+            if depth_of[increment.group] == usize::MAX {
+                depth_of[increment.group] = order.len();
+                order.push(increment.group);
+            }
+        }
+        for (group, depth) in depth_of.iter_mut().enumerate() {
+            if *depth == usize::MAX {
+                *depth = order.len();
+                order.push(group);
+            }
+        }
+
+        let mut ub_fixed = vec![false; m];
+        for (group, _) in preprocessing_result.ub_fix_result.f_1.iter().filter(|(_, item)| *item == 1) {
+            ub_fixed[*group] = true;
+        }
+        // items dominated in the LP relaxation are tried last
+        let mut dominated = vec![[false; 3]; m];
+        for (group, item) in &preprocessing_result.relaxation_result.f_0 {
+            dominated[*group][*item] = true;
+        }
+        // items fixed by the reduced costs, whatever a solution better than the incumbent takes
+        let reduced_cost_result = &preprocessing_result.reduced_cost_result;
+        let mut reduced_cost_fixed = vec![None; m];
+        let mut reduced_cost_excluded = vec![[false; 3]; m];
+        for (group, item) in &reduced_cost_result.f_1 {
+            reduced_cost_fixed[*group] = Some(item + 1);
+        }
+        for (group, item) in &reduced_cost_result.f_0 {
+            reduced_cost_excluded[*group][*item] = true;
+        }
+        let options: Vec<Vec<usize>> = (0..m).map(|i| {
+            if ub_fixed[i] {
+                return vec![3];
+            }
+            if let Some(option) = reduced_cost_fixed[i] {
+                return vec![option];
+            }
+            let mut options: Vec<usize> = vec![3, 2, 1];
+            options.retain(|k| !reduced_cost_excluded[i][*k - 1]);
+            options.sort_by_key(|k| dominated[i][*k - 1]);
+            options.push(0);
+            options
+        }).collect();
+
+        let mut best_choices = vec![0; m];
+        let mut best = 0;
+        let incumbent = self.incumbent.as_ref().map(|choices| choices.iter().zip(&sorted).map(|(choice, items)| {
+            match *choice {
+                0 => 0,
+                choice => items.iter().position(|k| *k == choice - 1).unwrap() + 1,
+            }
+        }).collect());
+        let candidates = [
+            Some(BranchAndBoundSolver::choices_of(&preprocessing_result.ub_fix_result.x_best)),
+            incumbent,
+        ];
+        for choices in candidates.into_iter().flatten() {
+            if let Some(value) = BranchAndBoundSolver::evaluate(problem, &choices) {
+                if value > best {
+                    best = value;
+                    best_choices = choices;
+                }
+            }
+        }
+        debug!("Branch and bound incumbent: {}", best);
+
+        let mut search = Search {
+            problem,
+            increments,
+            order,
+            depth_of,
+            options,
+            choices: vec![0; m],
+            best_choices,
+            best,
+            nodes: 0,
+            open_bound: f64::MIN,
+            cancellation: &self.cancellation,
+            stopped: false,
+        };
+        search.branch(0, 0, problem.capacity as i64);
+
+        let upper_bound = if search.stopped { search.open_bound.max(search.best as f64) } else { search.best as f64 };
+        let statistics = BranchAndBoundStatistics {
+            nodes: search.nodes,
+            upper_bound,
+            proven_optimal: !search.stopped,
+            best: search.best,
+        };
+        info!("Branch and bound explored {} nodes, gap: {:.6}", statistics.nodes, statistics.gap());
+
+        let choices: Vec<usize> = search.best_choices.iter().zip(&sorted).map(|(choice, items)| {
+            match *choice {
+                0 => 0,
+                choice => items[choice - 1] + 1,
+            }
+        }).collect();
+        Ok((Solution::from_choices(&self.problem, choices, 0), statistics))
+    }
+}
+
+impl Search<'_> {
+    // LP bound of the groups not fixed above `depth`
+    fn bound(&self, depth: usize, mut remaining_capacity: i64) -> f64 {
+        let mut bound = 0.0;
+        for increment in &self.increments {
+            if self.depth_of[increment.group] < depth {
+                continue;
+            }
+            if increment.cost <= remaining_capacity {
+                remaining_capacity -= increment.cost;
+                bound += increment.gain as f64;
+            } else {
+                bound += increment.gain as f64 * remaining_capacity as f64 / increment.cost as f64;
+                break;
+            }
+        }
+        bound
+    }
+
+    fn branch(&mut self, depth: usize, gain: i64, remaining_capacity: i64) {
+        self.nodes += 1;
+        if self.nodes % 1024 == 0 && self.cancellation.is_cancelled() {
+            self.stopped = true;
+        }
+        if gain > self.best {
+            self.best = gain;
+            self.best_choices = self.choices.clone();
+        }
+        if depth == self.order.len() {
+            return;
+        }
+
+        let bound = gain as f64 + self.bound(depth, remaining_capacity);
+        if bound.floor() as i64 <= self.best {
+            return;
+        }
+        if self.stopped {
+            self.open_bound = self.open_bound.max(bound);
+            return;
+        }
+
+        let group = self.order[depth];
+        for index in 0..self.options[group].len() {
+            let choice = self.options[group][index];
+            if choice == 0 {
+                self.branch(depth + 1, gain, remaining_capacity);
+            } else {
+                let item = &self.problem.data[group][choice - 1];
+                if item.cost > remaining_capacity {
+                    continue;
+                }
+                self.choices[group] = choice;
+                self.branch(depth + 1, gain + item.gain, remaining_capacity - item.cost);
+                self.choices[group] = 0;
+            }
+            if self.stopped {
+                // the siblings left are covered by the bound of this node
+                self.open_bound = self.open_bound.max(bound);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dp::DynamicProgrammingSolver;
+    use crate::structure::{make_item, make_problem};
+    use crate::utils::make_rng;
+    use rand::Rng;
+
+    fn make_random_problem(seed: u64, size: usize) -> Problem {
+        let mut rng = make_rng(seed);
+        let mut total_cost = 0;
+        let data: Vec<_> = (0..size).map(|_| {
+            let (a, b) = (rng.gen_range(10..100), rng.gen_range(10..100));
+            let (first_cost, second_cost) = (a.min(b), a.max(b) + 1);
+            let (first_gain, second_gain) = (rng.gen_range(10..100), rng.gen_range(10..100));
+            let third_cost = first_cost + second_cost - rng.gen_range(1..first_cost.min(10));
+            total_cost += third_cost;
+            vec![
+                make_item(first_gain, first_cost),
+                make_item(second_gain, second_cost),
+                make_item(first_gain + second_gain, third_cost),
+            ]
+        }).collect();
+        make_problem((total_cost / 3) as u32, data, size as i32)
+    }
+
+    #[test]
+    fn test_solve_matches_dynamic_programming() {
+        for seed in 0..20 {
+            let problem = make_random_problem(seed, 12);
+            let expected = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap();
+            let (solution, statistics) = BranchAndBoundSolver::new(problem).solve().unwrap();
+            assert_eq!(solution.fitness, expected.fitness);
+            assert!(statistics.proven_optimal);
+            assert_eq!(statistics.gap(), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_solve_keeps_better_incumbent() {
+        let problem = make_random_problem(3, 10);
+        let expected = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap();
+        let (solution, statistics) = BranchAndBoundSolver::new(problem)
+            .incumbent(expected.choices.clone())
+            .solve()
+            .unwrap();
+        assert_eq!(solution.fitness, expected.fitness);
+        assert!(statistics.nodes > 0);
+    }
+
+    #[test]
+    fn test_solve_reports_gap_when_stopped() {
+        let problem = make_random_problem(5, 200);
+        let (solution, statistics) = BranchAndBoundSolver::new(problem)
+            .time_limit(Duration::ZERO)
+            .solve()
+            .unwrap();
+        assert!(statistics.upper_bound >= solution.fitness as f64);
+        if !statistics.proven_optimal {
+            assert!(statistics.gap() >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_solve_sorts_unordered_groups() {
+        for seed in 0..20 {
+            let problem = make_random_problem(seed, 10);
+            let mut rng = make_rng(seed);
+            // shuffled items, with some costs tied
+            let data: Vec<Vec<_>> = problem.data.iter().map(|group| {
+                let mut group = group.clone();
+                if rng.gen_bool(0.3) {
+                    group[0].cost = group[1].cost;
+                }
+                group.swap(rng.gen_range(0..3), rng.gen_range(0..3));
+                group
+            }).collect();
+            let shuffled = make_problem(problem.capacity, data, problem.size);
+            let expected = DynamicProgrammingSolver::new(shuffled.clone(), usize::MAX).solve().unwrap();
+            let (solution, _) = BranchAndBoundSolver::new(shuffled.clone()).solve().unwrap();
+            assert_eq!(solution.fitness, expected.fitness);
+
+            // the incumbent is given and returned in the input order
+            let (solution, _) = BranchAndBoundSolver::new(shuffled.clone())
+                .incumbent(expected.choices.clone())
+                .solve()
+                .unwrap();
+            assert_eq!(solution.fitness, expected.fitness);
+            let gain: i64 = solution.choices.iter().enumerate()
+                .filter(|(_, choice)| **choice != 0)
+                .map(|(i, choice)| shuffled.data[i][choice - 1].gain)
+                .sum();
+            assert_eq!(gain, solution.fitness);
+        }
+    }
+
+    #[test]
+    fn test_solve_rejects_invalid_incumbent() {
+        let problem = make_random_problem(1, 3);
+        let result = BranchAndBoundSolver::new(problem.clone()).incumbent(vec![0, 4, 1]).solve();
+        assert!(matches!(result, Err(SolverError::InvalidIncumbent(_))));
+        let result = BranchAndBoundSolver::new(problem).incumbent(vec![0, 1]).solve();
+        assert!(matches!(result, Err(SolverError::InvalidIncumbent(_))));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::error::SolverError;
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use crate::utils;
+use log::{debug, info};
+use rand::rngs::SmallRng;
+use rand::Rng;
+use std::sync::Arc;
+
+/// Parameters of the biased random-key genetic algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrkgaSettings {
+    pub(crate) elite_fraction: f64,
+    pub(crate) mutant_fraction: f64,
+    pub(crate) inheritance: f64,
+}
+
+impl BrkgaSettings {
+    pub fn new() -> Self {
+        BrkgaSettings::default()
+    }
+
+    /// Share of the best individuals copied to the next generation.
+    pub fn elite_fraction(mut self, elite_fraction: f64) -> Self {
+        self.elite_fraction = elite_fraction;
+        self
+    }
+
+    /// Share of random individuals introduced at every generation.
+    pub fn mutant_fraction(mut self, mutant_fraction: f64) -> Self {
+        self.mutant_fraction = mutant_fraction;
+        self
+    }
+
+    /// Probability of an offspring inheriting the key of its elite parent.
+    pub fn inheritance(mut self, inheritance: f64) -> Self {
+        self.inheritance = inheritance;
+        self
+    }
+
+    fn validate(&self) -> Result<(), SolverError> {
+        if !(self.elite_fraction > 0.0 && self.mutant_fraction >= 0.0 && self.elite_fraction + self.mutant_fraction < 1.0) {
+            return Err(SolverError::InvalidBrkgaSettings(format!(
+                "elite fraction {} must be positive, mutant fraction {} not negative and their sum below 1",
+                self.elite_fraction, self.mutant_fraction
+            )));
+        }
+        if !(0.5..=1.0).contains(&self.inheritance) {
+            return Err(SolverError::InvalidBrkgaSettings(format!("inheritance {} must be in [0.5, 1]", self.inheritance)));
+        }
+        Ok(())
+    }
+}
+
+impl Default for BrkgaSettings {
+    fn default() -> Self {
+        BrkgaSettings {
+            elite_fraction: 0.2,
+            mutant_fraction: 0.15,
+            inheritance: 0.7,
+        }
+    }
+}
+
+/// Biased random-key genetic algorithm.
+///
+/// A chromosome holds one key in `[0, 1)` per item. The decoder visits the items by decreasing
+/// key times gain/cost efficiency and takes an item when its group is empty and it fits, or
+/// replaces the item of its group by a more profitable one when the extra cost fits, so every
+/// chromosome decodes to a feasible selection without repair. Each generation keeps the elite,
+/// adds random mutants and fills the rest with offsprings of an elite and a non-elite parent. The
+/// evolution stops after `stagnation_limit` generations without improvement.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{Brkga, Item, Problem};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let solution = Brkga::new(problem).population_size(20).seed(1).solve().unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// ```
+pub struct Brkga {
+    problem: Arc<Problem>,
+    settings: BrkgaSettings,
+    population_size: u32,
+    seed: u64,
+    stagnation_limit: u8,
+    cancellation: Cancellation,
+}
+
+struct Individual {
+    keys: Vec<f64>,
+    genes: Vec<usize>,
+    fitness: i64,
+}
+
+impl Brkga {
+    pub fn new(problem: impl Into<Arc<Problem>>) -> Self {
+        Brkga {
+            problem: problem.into(),
+            settings: BrkgaSettings::default(),
+            population_size: 100,
+            seed: 0,
+            stagnation_limit: 128,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    pub fn settings(mut self, settings: BrkgaSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn population_size(mut self, population_size: u32) -> Self {
+        self.population_size = population_size;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Generations without improvement before the evolution stops.
+    pub fn stagnation_limit(mut self, stagnation_limit: u8) -> Self {
+        self.stagnation_limit = stagnation_limit;
+        self
+    }
+
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub(crate) fn decode(&self, keys: &[f64]) -> (Vec<usize>, i64) {
+        let mut order: Vec<(usize, usize, f64)> = self.problem.data.iter().enumerate()
+            .flat_map(|(i, group)| group.iter().enumerate().map(move |(k, item)| (i, k, item)))
+            .map(|(i, k, item)| (i, k, keys[3 * i + k] * item.gain as f64 / item.cost.max(1) as f64))
+            .collect();
+        order.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        let mut remaining = self.problem.capacity as i64;
+        let mut genes = vec![0; self.problem.data.len()];
+        let mut gain = 0;
+        for (i, k, _) in order {
+            let item = &self.problem.data[i][k];
+            let (current_gain, current_cost) = match genes[i] {
+                0 => (0, 0),
+                g => (self.problem.data[i][g - 1].gain, self.problem.data[i][g - 1].cost),
+            };
+            if item.gain > current_gain && item.cost - current_cost <= remaining {
+                remaining -= item.cost - current_cost;
+                gain += item.gain - current_gain;
+                genes[i] = k + 1;
+            }
+        }
+        (genes, gain)
+    }
+
+    fn make_individual(&self, keys: Vec<f64>) -> Individual {
+        let (genes, fitness) = self.decode(&keys);
+        Individual { keys, genes, fitness }
+    }
+
+    fn random_keys(&self, rng: &mut SmallRng) -> Vec<f64> {
+        (0..3 * self.problem.data.len()).map(|_| rng.gen::<f64>()).collect()
+    }
+
+    pub fn solve(&self) -> Result<Solution, SolverError> {
+        self.settings.validate()?;
+        if self.problem.data.is_empty() {
+            return Err(SolverError::EmptyProblem);
+        }
+        let size = self.population_size as usize;
+        let elites = ((self.settings.elite_fraction * size as f64).round() as usize).max(1);
+        let mutants = (self.settings.mutant_fraction * size as f64).round() as usize;
+        if elites + mutants >= size {
+            return Err(SolverError::InvalidPopulationSize);
+        }
+        info!("Running BRKGA for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
+
+        let mut rng = utils::make_rng(self.seed);
+        let mut population: Vec<Individual> = (0..size).map(|_| {
+            let keys = self.random_keys(&mut rng);
+            self.make_individual(keys)
+        }).collect();
+        population.sort_by_key(|individual| std::cmp::Reverse(individual.fitness));
+        let (mut best_genes, mut best) = (population[0].genes.clone(), population[0].fitness);
+
+        let mut generations: u32 = 0;
+        let mut stagnant: u32 = 0;
+        while stagnant <= self.stagnation_limit as u32 && !self.cancellation.is_cancelled() {
+            let mut next: Vec<Individual> = population.drain(..elites).collect();
+            for _ in 0..mutants {
+                let keys = self.random_keys(&mut rng);
+                next.push(self.make_individual(keys));
+            }
+            while next.len() < size {
+                let elite = &next[rng.gen_range(0..elites)].keys;
+                let other = &population[rng.gen_range(0..population.len())].keys;
+                let keys = elite.iter().zip(other)
+                    .map(|(e, o)| if rng.gen::<f64>() < self.settings.inheritance { *e } else { *o })
+                    .collect();
+                next.push(self.make_individual(keys));
+            }
+            population = next;
+            population.sort_by_key(|individual| std::cmp::Reverse(individual.fitness));
+
+            generations += 1;
+            if population[0].fitness > best {
+                best = population[0].fitness;
+                best_genes = population[0].genes.clone();
+                stagnant = 0;
+            } else {
+                stagnant += 1;
+            }
+            info!("BRKGA generation: {} best: {}", generations, best);
+        }
+
+        debug!("BRKGA stopped after {} generations", generations);
+        Ok(Solution::from_choices(&self.problem, best_genes, generations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem, make_thirty_groups};
+
+    #[test]
+    fn test_decode_is_feasible() {
+        let problem = make_thirty_groups();
+        let brkga = Brkga::new(problem.clone());
+        let mut rng = utils::make_rng(0);
+        for _ in 0..50 {
+            let (genes, gain) = brkga.decode(&brkga.random_keys(&mut rng));
+            let (expected_gain, cost) = crate::genetic::operators::summary(&problem, &genes);
+            assert_eq!(gain, expected_gain);
+            assert!(cost <= 300);
+        }
+    }
+
+    #[test]
+    fn test_decode_follows_keys() {
+        let problem = make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+        ], 2);
+        let brkga = Brkga::new(problem);
+        // the keyed item comes first, the rest of the capacity goes to the first items in order
+        assert_eq!(brkga.decode(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0]), (vec![1, 3], 55));
+        assert_eq!(brkga.decode(&[0.0, 0.0, 1.0, 0.0, 0.0, 0.0]), (vec![3, 0], 30));
+    }
+
+    #[test]
+    fn test_solve_rejects_invalid_fractions() {
+        let settings = BrkgaSettings::new().elite_fraction(0.6).mutant_fraction(0.5);
+        let result = Brkga::new(make_thirty_groups()).settings(settings).solve();
+        assert!(matches!(result, Err(SolverError::InvalidBrkgaSettings(_))));
+    }
+}
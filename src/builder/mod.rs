@@ -0,0 +1,386 @@
+use crate::annealing::AnnealingSchedule;
+use crate::brkga::BrkgaSettings;
+use crate::de::DeSettings;
+use crate::error::SolverError;
+use crate::genetic::KnapsackGeneticAlgorithm;
+use crate::preprocessing::{PreprocessingResult, ProblemPreprocessor};
+use crate::pso::PsoSettings;
+use crate::structure::configuration::{ConfigurationByGenerations, PopulationSize};
+use crate::structure::constraint_handling::ConstraintHandling;
+use crate::structure::problem::Problem;
+use crate::structure::seeding_mix::{SeedingMix, SeedingStrategy};
+use crate::tabu::TabuSettings;
+use std::sync::Arc;
+
+/// Configures and validates a [`KnapsackGeneticAlgorithm`], the other settings are used by the
+/// solvers of the [`SolverRegistry`](crate::solver::SolverRegistry).
+///
+/// ```
+/// use discounted_zeroone_knapsack::{GeneticAlgorithm, Item, PopulationSize, Problem, SolverBuilder};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let mut solver = SolverBuilder::new()
+///     .problem(problem)
+///     .seed(7)
+///     .population_size(PopulationSize::PerGroup(10.0))
+///     .enhanced_seeding(true)
+///     .build()
+///     .unwrap();
+/// let solution = solver.run();
+/// assert!(solution.cost() <= 20);
+///
+/// assert!(SolverBuilder::new().build().is_err());
+/// ```
+#[derive(Clone)]
+pub struct SolverBuilder {
+    problem: Option<Arc<Problem>>,
+    pub(crate) population_size: PopulationSize,
+    preprocessing_result: Option<PreprocessingResult>,
+    pub(crate) configuration: ConfigurationByGenerations,
+    pub(crate) annealing_schedule: AnnealingSchedule,
+    pub(crate) tabu_settings: TabuSettings,
+    pub(crate) pso_settings: PsoSettings,
+    pub(crate) de_settings: DeSettings,
+    pub(crate) brkga_settings: BrkgaSettings,
+    pub(crate) memory_budget: usize,
+    pub(crate) epsilon: f64,
+    pub(crate) ga_incumbent: bool,
+    pub(crate) portfolio_size: usize,
+}
+
+impl SolverBuilder {
+    pub fn new() -> Self {
+        SolverBuilder {
+            problem: None,
+            population_size: PopulationSize::PerGroup(5.0),
+            preprocessing_result: None,
+            configuration: ConfigurationByGenerations::default(),
+            annealing_schedule: AnnealingSchedule::default(),
+            tabu_settings: TabuSettings::default(),
+            pso_settings: PsoSettings::default(),
+            de_settings: DeSettings::default(),
+            brkga_settings: BrkgaSettings::default(),
+            memory_budget: usize::MAX,
+            epsilon: 0.1,
+            ga_incumbent: false,
+            portfolio_size: 4,
+        }
+    }
+
+    pub fn problem(mut self, problem: impl Into<Arc<Problem>>) -> Self {
+        self.problem = Some(problem.into());
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.configuration.seed = seed;
+        self
+    }
+
+    pub fn population_size(mut self, population_size: impl Into<PopulationSize>) -> Self {
+        self.population_size = population_size.into();
+        self
+    }
+
+    /// Initial mutation probability in thousandths, decreased every 10 generations.
+    pub fn mutation_factor(mut self, mutation_factor: u16) -> Self {
+        self.configuration.initial_mutation_factor = mutation_factor;
+        self
+    }
+
+    /// Generations without improvement before the evolution stops.
+    pub fn stagnation_limit(mut self, stagnation_limit: u8) -> Self {
+        self.configuration.no_upgrade_limit = stagnation_limit;
+        self
+    }
+
+    /// Seeds the population with the preprocessing, the preprocessing is run by
+    /// [`SolverBuilder::build`] when no result has been given.
+    pub fn enhanced_seeding(mut self, enabled: bool) -> Self {
+        self.configuration.enhanced_enabled = enabled;
+        self
+    }
+
+    pub fn preprocessing_result(mut self, preprocessing_result: PreprocessingResult) -> Self {
+        self.preprocessing_result = Some(preprocessing_result);
+        self
+    }
+
+    pub fn seeding_mix(mut self, seeding_mix: SeedingMix) -> Self {
+        self.configuration.seeding_mix = seeding_mix;
+        self
+    }
+
+    pub fn grasp_rcl_size(mut self, grasp_rcl_size: usize) -> Self {
+        self.configuration.grasp_rcl_size = grasp_rcl_size;
+        self
+    }
+
+    pub fn seeding_file(mut self, seeding_file: impl Into<String>) -> Self {
+        self.configuration.seeding_file = Some(seeding_file.into());
+        self
+    }
+
+    pub fn annealing_schedule(mut self, schedule: AnnealingSchedule) -> Self {
+        self.annealing_schedule = schedule;
+        self
+    }
+
+    pub fn tabu_settings(mut self, settings: TabuSettings) -> Self {
+        self.tabu_settings = settings;
+        self
+    }
+
+    pub fn pso_settings(mut self, settings: PsoSettings) -> Self {
+        self.pso_settings = settings;
+        self
+    }
+
+    pub fn de_settings(mut self, settings: DeSettings) -> Self {
+        self.de_settings = settings;
+        self
+    }
+
+    pub fn brkga_settings(mut self, settings: BrkgaSettings) -> Self {
+        self.brkga_settings = settings;
+        self
+    }
+
+    /// Largest amount of memory, in bytes, the dynamic programming solvers are allowed to use.
+    pub fn memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Approximation factor of the FPTAS.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Starts branch and bound from the best chromosome of the genetic algorithm.
+    pub fn ga_incumbent(mut self, enabled: bool) -> Self {
+        self.ga_incumbent = enabled;
+        self
+    }
+
+    /// Number of genetic algorithm configurations run by the portfolio.
+    pub fn portfolio_size(mut self, portfolio_size: usize) -> Self {
+        self.portfolio_size = portfolio_size;
+        self
+    }
+
+    /// Stops the evolution once the relative gap to the Dantzig bound is at most `gap_tolerance`,
+    /// at 0 it stops only on proven optimality.
+    pub fn gap_tolerance(mut self, gap_tolerance: f64) -> Self {
+        self.configuration.gap_tolerance = gap_tolerance;
+        self
+    }
+
+    /// Keeps the genetic operators to the options left by the preprocessing fixings and makes the
+    /// mutation favour the items not dominated in the LP relaxation, only effective with the
+    /// enhanced seeding.
+    pub fn domain_constraints(mut self, enabled: bool) -> Self {
+        self.configuration.domain_constrained = enabled;
+        self
+    }
+
+    /// How the genetic algorithm treats offspring exceeding the capacity, repaired by default.
+    pub fn constraint_handling(mut self, constraint_handling: ConstraintHandling) -> Self {
+        self.configuration.constraint_handling = constraint_handling;
+        self
+    }
+
+    fn validate_problem(problem: &Problem) -> Result<(), SolverError> {
+        if problem.data.is_empty() {
+            return Err(SolverError::EmptyProblem);
+        }
+        match problem.data.iter().position(|group| group.len() != 3) {
+            Some(group) => Err(SolverError::InvalidGroup { group, items: problem.data[group].len() }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn build(self) -> Result<KnapsackGeneticAlgorithm, SolverError> {
+        let problem = self.problem.ok_or(SolverError::MissingProblem)?;
+        SolverBuilder::validate_problem(&problem)?;
+
+        let mut configuration = self.configuration;
+        configuration.population_size = self.population_size.resolve(problem.size);
+        if configuration.population_size < 2 {
+            return Err(SolverError::InvalidPopulationSize);
+        }
+        if configuration.initial_mutation_factor > 1000 {
+            return Err(SolverError::InvalidMutationFactor(configuration.initial_mutation_factor));
+        }
+        if !(0.0..1.0).contains(&configuration.gap_tolerance) {
+            return Err(SolverError::InvalidGapTolerance(configuration.gap_tolerance));
+        }
+        if configuration.seeding_mix.uses(SeedingStrategy::File) && configuration.seeding_file.is_none() {
+            return Err(SolverError::MissingSeedingFile);
+        }
+
+        let preprocessing_result = match self.preprocessing_result {
+            Some(result) => {
+                let preprocessed = result.relaxation_result.x_up.len();
+                if preprocessed != 0 && preprocessed != problem.data.len() {
+                    return Err(SolverError::PreprocessingMismatch { groups: problem.data.len(), preprocessed });
+                }
+                result
+            }
+            None if configuration.enhanced_enabled => ProblemPreprocessor::new(&problem).process_problem(),
+            None => PreprocessingResult::empty(),
+        };
+
+        KnapsackGeneticAlgorithm::try_init(problem, Box::new(configuration), preprocessing_result)
+    }
+}
+
+impl Default for SolverBuilder {
+    fn default() -> Self {
+        SolverBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetic::GeneticAlgorithm;
+    use crate::structure::fix_result::UBFixResult;
+    use crate::structure::reduced_cost_result::ReducedCostResult;
+    use crate::structure::{make_item, make_problem};
+
+    fn make_test_problem() -> Problem {
+        make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+        ], 2)
+    }
+
+    #[test]
+    fn test_build_requires_problem() {
+        assert_eq!(SolverBuilder::new().build().err(), Some(SolverError::MissingProblem));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_problem() {
+        let empty = make_problem(10, vec![], 0);
+        assert_eq!(SolverBuilder::new().problem(empty).build().err(), Some(SolverError::EmptyProblem));
+
+        let short = make_problem(10, vec![vec![make_item(1, 1), make_item(2, 2)]], 1);
+        assert_eq!(
+            SolverBuilder::new().problem(short).build().err(),
+            Some(SolverError::InvalidGroup { group: 0, items: 2 })
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_configuration() {
+        let result = SolverBuilder::new().problem(make_test_problem()).population_size(0).build();
+        assert_eq!(result.err(), Some(SolverError::InvalidPopulationSize));
+
+        let result = SolverBuilder::new().problem(make_test_problem()).population_size(1).build();
+        assert_eq!(result.err(), Some(SolverError::InvalidPopulationSize));
+
+        let result = SolverBuilder::new().problem(make_test_problem()).mutation_factor(1001).build();
+        assert_eq!(result.err(), Some(SolverError::InvalidMutationFactor(1001)));
+
+        let mix: SeedingMix = "file=1".parse().unwrap();
+        let result = SolverBuilder::new().problem(make_test_problem()).seeding_mix(mix).build();
+        assert_eq!(result.err(), Some(SolverError::MissingSeedingFile));
+
+        let result = SolverBuilder::new().problem(make_test_problem()).gap_tolerance(1.0).build();
+        assert_eq!(result.err(), Some(SolverError::InvalidGapTolerance(1.0)));
+    }
+
+    #[test]
+    fn test_run_stops_on_proven_optimality() {
+        let roomy = make_problem(100, make_test_problem().data, 2);
+        let mut solver = SolverBuilder::new().problem(roomy).population_size(50).stagnation_limit(200).build().unwrap();
+        let solution = solver.run();
+        assert_eq!(solution.fitness(), 75);
+        assert!(solver.proven_optimal());
+        assert!(solution.generations() < 200);
+    }
+
+    #[test]
+    fn test_penalties_return_feasible_solutions() {
+        let data: Vec<Vec<_>> = (0..30).map(|i| {
+            vec![make_item(10 + i % 7, 5 + i % 5), make_item(20 + i % 11, 10 + i % 3), make_item(35 + i % 13, 14 + i % 4)]
+        }).collect();
+        let problem = make_problem(200, data, 30);
+        for handling in ["repair", "death", "static", "static:0.5", "adaptive"] {
+            let mut solver = SolverBuilder::new().problem(problem.clone())
+                .population_size(40)
+                .constraint_handling(handling.parse().unwrap())
+                .build()
+                .unwrap();
+            let solution = solver.run();
+            assert!(solution.cost() <= 200, "{} returned an infeasible solution", handling);
+            assert!(solution.fitness() > 0);
+        }
+    }
+
+    #[test]
+    fn test_enhanced_run_reaches_lp_dominated_optimum() {
+        // the only item fitting is LP-dominated by the second item of its group, the domain keeps it
+        let problem = make_problem(5, make_test_problem().data, 2);
+        let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+        assert!(preprocessing.relaxation_result.f_0.contains(&(0, 0)));
+        let mut solver = SolverBuilder::new().problem(problem)
+            .population_size(20)
+            .enhanced_seeding(true)
+            .preprocessing_result(preprocessing)
+            .build()
+            .unwrap();
+        assert!(solver.domain_constrained());
+        assert_eq!(solver.run().fitness(), 10);
+    }
+
+    #[test]
+    fn test_constrained_run_falls_back_on_ub_fix_solution() {
+        // the fixings leave every group empty, as they may when no solution beats the UB-fix one
+        let problem = make_test_problem();
+        let mut preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+        let excluded = (0..2).flat_map(|group| (0..3).map(move |item| (group, item))).collect();
+        preprocessing.reduced_cost_result = ReducedCostResult::new(excluded, vec![], 0.0);
+        preprocessing.ub_fix_result = UBFixResult::new(vec![], vec![[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]], 55.0);
+        let mut solver = SolverBuilder::new().problem(problem)
+            .population_size(10)
+            .enhanced_seeding(true)
+            .preprocessing_result(preprocessing)
+            .build()
+            .unwrap();
+        assert!(solver.domain_constrained());
+        let solution = solver.run();
+        assert_eq!(solution.choices(), vec![1, 3]);
+        assert_eq!(solution.fitness(), 55);
+    }
+
+    #[test]
+    fn test_build_rejects_unusable_seeding_file() {
+        let mix: SeedingMix = "file=1".parse().unwrap();
+        let builder = SolverBuilder::new().problem(make_test_problem()).seeding_mix(mix);
+        let result = builder.clone().seeding_file("missing_individuals.txt").build();
+        assert!(matches!(result.err(), Some(SolverError::InvalidSeedingFile(_))));
+
+        let path = std::env::temp_dir().join(format!("individuals-{}.txt", std::process::id()));
+        for (content, valid) in [("0 4\n", false), ("0 1 2\n", false), ("\n", false), ("1 3\n\n2 0\n", true)] {
+            std::fs::write(&path, content).unwrap();
+            let result = builder.clone().seeding_file(path.to_str().unwrap()).build();
+            assert_eq!(result.is_ok(), valid, "{:?}", content);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_build_rejects_preprocessing_of_another_problem() {
+        let other = make_problem(20, vec![vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)]], 1);
+        let preprocessing = ProblemPreprocessor::new(&other).process_problem();
+        let result = SolverBuilder::new().problem(make_test_problem()).preprocessing_result(preprocessing).build();
+        assert_eq!(result.err(), Some(SolverError::PreprocessingMismatch { groups: 2, preprocessed: 1 }));
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::SolverError;
+use crate::genetic::operators;
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use crate::utils;
+use log::{debug, info};
+use rand::Rng;
+use std::sync::Arc;
+
+/// Parameters of the discrete differential evolution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeSettings {
+    pub(crate) differential_weight: f64,
+    pub(crate) crossover_rate: f64,
+}
+
+impl DeSettings {
+    pub fn new() -> Self {
+        DeSettings::default()
+    }
+
+    /// Scale `F` of the difference of two individuals.
+    pub fn differential_weight(mut self, differential_weight: f64) -> Self {
+        self.differential_weight = differential_weight;
+        self
+    }
+
+    /// Probability `CR` of taking a gene of the mutant.
+    pub fn crossover_rate(mut self, crossover_rate: f64) -> Self {
+        self.crossover_rate = crossover_rate;
+        self
+    }
+
+    fn validate(&self) -> Result<(), SolverError> {
+        if !(self.differential_weight > 0.0 && self.differential_weight.is_finite()) {
+            return Err(SolverError::InvalidDeSettings(format!("differential weight {} must be positive", self.differential_weight)));
+        }
+        if !(0.0..=1.0).contains(&self.crossover_rate) {
+            return Err(SolverError::InvalidDeSettings(format!("crossover rate {} must be in [0, 1]", self.crossover_rate)));
+        }
+        Ok(())
+    }
+}
+
+impl Default for DeSettings {
+    fn default() -> Self {
+        DeSettings {
+            differential_weight: 1.0,
+            crossover_rate: 0.3,
+        }
+    }
+}
+
+/// Discrete differential evolution over the ring of genes modulo 4.
+///
+/// The mutant of every target is `x_r1 + round(F·(x_r2 - x_r3)) mod 4` for three other random
+/// individuals, mixed with the target by binomial crossover. The trial is repaired with the rule
+/// of the genetic algorithm and replaces the target when it is at least as good. The evolution
+/// stops after `stagnation_limit` generations without improvement.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{DifferentialEvolution, Item, Problem};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let solution = DifferentialEvolution::new(problem).population_size(20).seed(1).solve().unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// ```
+pub struct DifferentialEvolution {
+    problem: Arc<Problem>,
+    settings: DeSettings,
+    population_size: u32,
+    seed: u64,
+    stagnation_limit: u8,
+    cancellation: Cancellation,
+}
+
+impl DifferentialEvolution {
+    pub fn new(problem: impl Into<Arc<Problem>>) -> Self {
+        DifferentialEvolution {
+            problem: problem.into(),
+            settings: DeSettings::default(),
+            population_size: 100,
+            seed: 0,
+            stagnation_limit: 128,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    pub fn settings(mut self, settings: DeSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Number of individuals, at least 4 so that three of them differ from the target.
+    pub fn population_size(mut self, population_size: u32) -> Self {
+        self.population_size = population_size;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Generations without improvement before the evolution stops.
+    pub fn stagnation_limit(mut self, stagnation_limit: u8) -> Self {
+        self.stagnation_limit = stagnation_limit;
+        self
+    }
+
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    // x_r1 + round(F·(x_r2 - x_r3)) on the ring of genes modulo 4
+    fn mutant_gene(&self, r1: usize, r2: usize, r3: usize) -> usize {
+        let difference = (self.settings.differential_weight * (r2 as f64 - r3 as f64)).round() as i64;
+        (r1 as i64 + difference).rem_euclid(4) as usize
+    }
+
+    pub fn solve(&self) -> Result<Solution, SolverError> {
+        self.settings.validate()?;
+        if self.problem.data.is_empty() {
+            return Err(SolverError::EmptyProblem);
+        }
+        if self.population_size < 4 {
+            return Err(SolverError::InvalidPopulationSize);
+        }
+        info!("Running differential evolution for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
+
+        let m = self.problem.data.len();
+        let size = self.population_size as usize;
+        let mut rng = utils::make_rng(self.seed);
+        let mut population: Vec<(Vec<usize>, i64)> = (0..size).map(|_| {
+            let mut genes: Vec<usize> = (0..m).map(|_| rng.gen_range(0..4)).collect();
+            operators::repair(&self.problem, &mut genes);
+            let gain = operators::summary(&self.problem, &genes).0;
+            (genes, gain)
+        }).collect();
+        let mut best = population.iter().max_by_key(|(_, gain)| *gain).cloned().unwrap();
+
+        let mut generations: u32 = 0;
+        let mut stagnant: u32 = 0;
+        while stagnant <= self.stagnation_limit as u32 && !self.cancellation.is_cancelled() {
+            let mut improved = false;
+            for target in 0..size {
+                let mut picked = [target; 3];
+                for k in 0..3 {
+                    while picked[k] == target || picked[..k].contains(&picked[k]) {
+                        picked[k] = rng.gen_range(0..size);
+                    }
+                }
+                let [r1, r2, r3] = picked.map(|r| &population[r].0);
+                let forced = rng.gen_range(0..m);
+                let mut trial: Vec<usize> = (0..m).map(|j| {
+                    if j == forced || rng.gen::<f64>() < self.settings.crossover_rate {
+                        self.mutant_gene(r1[j], r2[j], r3[j])
+                    } else {
+                        population[target].0[j]
+                    }
+                }).collect();
+                operators::repair(&self.problem, &mut trial);
+                let gain = operators::summary(&self.problem, &trial).0;
+                if gain >= population[target].1 {
+                    if gain > best.1 {
+                        best = (trial.clone(), gain);
+                        improved = true;
+                    }
+                    population[target] = (trial, gain);
+                }
+            }
+            generations += 1;
+            stagnant = if improved { 0 } else { stagnant + 1 };
+            info!("Differential evolution generation: {} best: {}", generations, best.1);
+        }
+
+        let (choices, _) = best;
+        debug!("Differential evolution stopped after {} generations", generations);
+        Ok(Solution::from_choices(&self.problem, choices, generations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem, make_thirty_groups};
+
+    #[test]
+    fn test_mutant_gene_wraps_around() {
+        let de = DifferentialEvolution::new(make_thirty_groups());
+        assert_eq!(de.mutant_gene(3, 1, 2), 2);
+        assert_eq!(de.mutant_gene(0, 0, 3), 1);
+        assert_eq!(de.mutant_gene(3, 3, 0), 2);
+
+        let de = de.settings(DeSettings::new().differential_weight(0.5));
+        assert_eq!(de.mutant_gene(1, 3, 0), 3);
+        assert_eq!(de.mutant_gene(1, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_solve_is_feasible() {
+        let settings = DeSettings::new().differential_weight(0.5).crossover_rate(0.9);
+        let solution = DifferentialEvolution::new(make_thirty_groups()).settings(settings).population_size(30).seed(4).solve().unwrap();
+        assert!(solution.cost() <= 300);
+    }
+
+    #[test]
+    fn test_solve_rejects_small_population() {
+        let problem = make_problem(10, vec![vec![make_item(1, 1), make_item(2, 2), make_item(3, 3)]], 1);
+        let result = DifferentialEvolution::new(problem).population_size(3).solve();
+        assert_eq!(result.unwrap_err(), SolverError::InvalidPopulationSize);
+    }
+}
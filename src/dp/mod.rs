@@ -0,0 +1,174 @@
+use crate::error::SolverError;
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use log::{debug, info};
+use std::sync::Arc;
+
+/// Exact group dynamic programming over the capacity.
+///
+/// `best[c]` holds the best gain using at most capacity `c` with the groups processed so far.
+/// Each group is processed in O(C), the values take O(C) memory and the choice taken for every
+/// (group, capacity) pair is kept in a 2-bit table used to rebuild the solution.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{DynamicProgrammingSolver, Item, Problem};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let solution = DynamicProgrammingSolver::new(problem, 64 * 1024 * 1024).solve().unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// assert_eq!(solution.choices(), &[1, 3]);
+/// ```
+pub struct DynamicProgrammingSolver {
+    problem: Arc<Problem>,
+    memory_budget: usize,
+    cancellation: Cancellation,
+}
+
+pub(crate) struct ChoiceTable {
+    bits: Vec<u64>,
+    width: usize,
+}
+
+impl ChoiceTable {
+    pub(crate) fn new(groups: usize, width: usize) -> Self {
+        ChoiceTable {
+            bits: vec![0; (groups * width).div_ceil(32)],
+            width,
+        }
+    }
+
+    pub(crate) fn set(&mut self, group: usize, column: usize, choice: usize) {
+        let cell = group * self.width + column;
+        let shift = (cell % 32) * 2;
+        self.bits[cell / 32] = (self.bits[cell / 32] & !(0b11 << shift)) | ((choice as u64) << shift);
+    }
+
+    pub(crate) fn get(&self, group: usize, column: usize) -> usize {
+        let cell = group * self.width + column;
+        ((self.bits[cell / 32] >> ((cell % 32) * 2)) & 0b11) as usize
+    }
+}
+
+impl DynamicProgrammingSolver {
+    /// `memory_budget` is the largest amount of memory, in bytes, the solver is allowed to use.
+    pub fn new(problem: impl Into<Arc<Problem>>, memory_budget: usize) -> Self {
+        DynamicProgrammingSolver { problem: problem.into(), memory_budget, cancellation: Cancellation::new() }
+    }
+
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Bytes needed by the choice table and the value row.
+    pub fn required_memory(problem: &Problem) -> usize {
+        let width = problem.capacity as usize + 1;
+        (problem.size as usize * width).div_ceil(32) * 8 + width * 8
+    }
+
+    pub fn solve(&self) -> Result<Solution, SolverError> {
+        let required = DynamicProgrammingSolver::required_memory(&self.problem);
+        if required > self.memory_budget {
+            return Err(SolverError::MemoryBudgetExceeded { required, budget: self.memory_budget });
+        }
+        info!("Running dynamic programming for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
+
+        let capacity = self.problem.capacity as usize;
+        let mut best = vec![0i64; capacity + 1];
+        let mut choices = ChoiceTable::new(self.problem.data.len(), capacity + 1);
+
+        for (i, group) in self.problem.data.iter().enumerate() {
+            if self.cancellation.is_cancelled() {
+                return Err(SolverError::Cancelled);
+            }
+            // descending capacity keeps best[c - cost] at the value of the previous groups
+            for c in (0..=capacity).rev() {
+                let mut value = best[c];
+                let mut choice = 0;
+                for (k, item) in group.iter().enumerate() {
+                    if item.cost <= c as i64 {
+                        let candidate = best[c - item.cost as usize] + item.gain;
+                        if candidate > value {
+                            value = candidate;
+                            choice = k + 1;
+                        }
+                    }
+                }
+                if choice != 0 {
+                    best[c] = value;
+                    choices.set(i, c, choice);
+                }
+            }
+            debug!("Group {} processed, best value: {}", i, best[capacity]);
+        }
+
+        let mut genes = vec![0; self.problem.data.len()];
+        let mut c = capacity;
+        for i in (0..self.problem.data.len()).rev() {
+            let choice = choices.get(i, c);
+            if choice != 0 {
+                genes[i] = choice;
+                c -= self.problem.data[i][choice - 1].cost as usize;
+            }
+        }
+
+        Ok(Solution::from_choices(&self.problem, genes, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    fn make_problem_low_capacity() -> Problem {
+        make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+            vec![make_item(20, 1), make_item(35, 35), make_item(55, 35)],
+            vec![make_item(11, 3), make_item(22, 15), make_item(33, 16)],
+        ], 4)
+    }
+
+    // enumerates the 4^m choice vectors
+    fn brute_force(problem: &Problem) -> i64 {
+        let m = problem.size as u32;
+        (0..4usize.pow(m)).filter_map(|code| {
+            let (mut gain, mut cost, mut rest) = (0, 0, code);
+            for group in &problem.data {
+                if rest % 4 != 0 {
+                    gain += group[rest % 4 - 1].gain;
+                    cost += group[rest % 4 - 1].cost;
+                }
+                rest /= 4;
+            }
+            (cost <= problem.capacity as i64).then_some(gain)
+        }).max().unwrap()
+    }
+
+    #[test]
+    fn test_solve_matches_brute_force() {
+        let problem = make_problem_low_capacity();
+        let solution = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap();
+        assert_eq!(solution.fitness, brute_force(&problem));
+        assert!(solution.cost <= problem.capacity as i64);
+
+        let gain: i64 = solution.choices.iter().enumerate()
+            .filter(|(_, c)| **c != 0)
+            .map(|(i, c)| problem.data[i][*c - 1].gain)
+            .sum();
+        assert_eq!(gain, solution.fitness);
+    }
+
+    #[test]
+    fn test_solve_refuses_over_budget() {
+        let problem = make_problem_low_capacity();
+        let required = DynamicProgrammingSolver::required_memory(&problem);
+        let result = DynamicProgrammingSolver::new(problem, required - 1).solve();
+        assert_eq!(result.err(), Some(SolverError::MemoryBudgetExceeded { required, budget: required - 1 }));
+    }
+}
@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::fmt;
+
+/// Reasons why a solver can not be built or run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverError {
+    MissingProblem,
+    EmptyProblem,
+    InvalidGroup { group: usize, items: usize },
+    InvalidPopulationSize,
+    InvalidMutationFactor(u16),
+    InvalidGapTolerance(f64),
+    PreprocessingMismatch { groups: usize, preprocessed: usize },
+    MissingSeedingFile,
+    InvalidSeedingFile(String),
+    MemoryBudgetExceeded { required: usize, budget: usize },
+    InvalidIncumbent(String),
+    InvalidEpsilon(f64),
+    UnknownAlgorithm(String),
+    Cancelled,
+    InvalidAnnealingSchedule(String),
+    InvalidTabuSettings(String),
+    InvalidPsoSettings(String),
+    InvalidDeSettings(String),
+    InvalidBrkgaSettings(String),
+    EmptyPortfolio,
+    MissingDeadline,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::MissingProblem => write!(f, "no problem has been set"),
+            SolverError::EmptyProblem => write!(f, "the problem has no groups"),
+            SolverError::InvalidGroup { group, items } => {
+                write!(f, "group {} has {} items, every group must have exactly 3 items", group, items)
+            }
+            SolverError::InvalidPopulationSize => write!(f, "population size is too small for the algorithm"),
+            SolverError::InvalidMutationFactor(factor) => {
+                write!(f, "mutation factor {} is out of range, it must be between 0 and 1000", factor)
+            }
+            SolverError::InvalidGapTolerance(tolerance) => {
+                write!(f, "gap tolerance {} is out of range, it must be in [0, 1)", tolerance)
+            }
+            SolverError::PreprocessingMismatch { groups, preprocessed } => write!(
+                f,
+                "preprocessing result covers {} groups but the problem has {} groups",
+                preprocessed, groups
+            ),
+            SolverError::MissingSeedingFile => write!(f, "seeding strategy 'file' requires a seeding file"),
+            SolverError::InvalidSeedingFile(reason) => write!(f, "invalid seeding file: {}", reason),
+            SolverError::MemoryBudgetExceeded { required, budget } => write!(
+                f,
+                "dynamic programming needs {} bytes but the memory budget is {} bytes",
+                required, budget
+            ),
+            SolverError::InvalidEpsilon(epsilon) => write!(f, "epsilon {} is out of range, it must be in (0, 1)", epsilon),
+            SolverError::UnknownAlgorithm(name) => write!(f, "no solver is registered under the name '{}'", name),
+            SolverError::InvalidAnnealingSchedule(reason) => write!(f, "invalid annealing schedule: {}", reason),
+            SolverError::InvalidTabuSettings(reason) => write!(f, "invalid tabu search settings: {}", reason),
+            SolverError::InvalidPsoSettings(reason) => write!(f, "invalid particle swarm settings: {}", reason),
+            SolverError::InvalidDeSettings(reason) => write!(f, "invalid differential evolution settings: {}", reason),
+            SolverError::InvalidBrkgaSettings(reason) => write!(f, "invalid BRKGA settings: {}", reason),
+            SolverError::EmptyPortfolio => write!(f, "the portfolio has no member"),
+            SolverError::MissingDeadline => write!(f, "the portfolio runs until a deadline, a time limit is required"),
+            SolverError::Cancelled => write!(f, "the solver has been cancelled before finding a solution"),
+            SolverError::InvalidIncumbent(reason) => write!(f, "invalid incumbent: {}", reason),
+        }
+    }
+}
+
+impl Error for SolverError {}
@@ -0,0 +1,252 @@
+use crate::dp::ChoiceTable;
+use crate::error::SolverError;
+use crate::generator::{GreedyChromosomeGenerator, InitializationStrategy};
+use crate::preprocessing::ProblemPreprocessor;
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use log::{debug, info};
+use std::sync::Arc;
+
+/// Fully polynomial approximation scheme based on profit scaling.
+///
+/// Gains are divided by `K = ε·LB/m`, where `LB` is the best of the whole increments taken by the
+/// Dantzig greedy, of the ratio greedy and of the most profitable item, then a dynamic programming
+/// over the scaled profit keeps the lightest selection reaching every profit. Rounding loses less
+/// than `K` per group, so the solution is worth at least `OPT - ε·LB >= (1 - ε)·OPT`. The Dantzig
+/// bound `UB` adds less than one item to the whole increments, so `UB <= 2·LB`, scaled profits
+/// never exceed `UB/K <= 2m/ε` and the table has O(m/ε) columns, for a time in O(m²/ε).
+///
+/// ```
+/// use discounted_zeroone_knapsack::{FptasSolver, Item, Problem};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let (solution, statistics) = FptasSolver::new(problem, 0.1).solve().unwrap();
+/// assert!(solution.fitness() as f64 >= 0.9 * 55.0);
+/// assert!(statistics.upper_bound() >= 55.0);
+/// ```
+pub struct FptasSolver {
+    problem: Arc<Problem>,
+    epsilon: f64,
+    memory_budget: usize,
+    cancellation: Cancellation,
+}
+
+#[derive(Debug, Clone)]
+pub struct FptasStatistics {
+    pub(crate) epsilon: f64,
+    pub(crate) scale: f64,
+    pub(crate) upper_bound: f64,
+    pub(crate) best: i64,
+}
+
+impl FptasStatistics {
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Factor the gains have been divided by.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Bound on the optimum given by the guarantee or by the Dantzig bound, whichever is lower.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+
+    pub fn gap(&self) -> f64 {
+        if self.upper_bound <= 0.0 {
+            return 0.0;
+        }
+        ((self.upper_bound - self.best as f64) / self.upper_bound).max(0.0)
+    }
+}
+
+impl FptasSolver {
+    pub fn new(problem: impl Into<Arc<Problem>>, epsilon: f64) -> Self {
+        FptasSolver {
+            problem: problem.into(),
+            epsilon,
+            memory_budget: usize::MAX,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    /// Largest amount of memory, in bytes, the profit table is allowed to use.
+    pub fn memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    // best of the integer prefix of the Dantzig greedy, of the ratio greedy and of the most
+    // profitable item, so the Dantzig bound is at most twice the result
+    fn lower_bound(&self, prefix: Vec<usize>) -> Solution {
+        let capacity = self.problem.capacity as i64;
+        let greedy = GreedyChromosomeGenerator::new(&self.problem).generate_chromosome().choices();
+        let best_item = self.problem.data.iter().enumerate()
+            .flat_map(|(i, group)| group.iter().enumerate().map(move |(k, item)| (i, k, item)))
+            .filter(|(_, _, item)| item.cost <= capacity)
+            .max_by_key(|(_, _, item)| item.gain)
+            .map(|(i, k, _)| {
+                let mut choices = vec![0; self.problem.data.len()];
+                choices[i] = k + 1;
+                choices
+            });
+        [Some(prefix), Some(greedy), best_item].into_iter()
+            .flatten()
+            .map(|choices| Solution::from_choices(&self.problem, choices, 0))
+            .max_by_key(|solution| solution.fitness)
+            .unwrap()
+    }
+
+    pub fn solve(&self) -> Result<(Solution, FptasStatistics), SolverError> {
+        if !(self.epsilon > 0.0 && self.epsilon < 1.0) {
+            return Err(SolverError::InvalidEpsilon(self.epsilon));
+        }
+        info!("Running FPTAS with epsilon: {} for knapsack capacity: {}, selection size: {} ", self.epsilon, self.problem.capacity, self.problem.size);
+
+        let m = self.problem.data.len();
+        let capacity = self.problem.capacity as i64;
+        let (dantzig_bound, prefix) = ProblemPreprocessor::new(&self.problem).dantzig_greedy();
+        let lower_bound = self.lower_bound(prefix);
+        if lower_bound.fitness <= 0 {
+            let statistics = FptasStatistics { epsilon: self.epsilon, scale: 1.0, upper_bound: dantzig_bound, best: 0 };
+            return Ok((lower_bound, statistics));
+        }
+
+        // below 1 the scaling would only enlarge the table, the dynamic programming is then exact
+        let scale = (self.epsilon * lower_bound.fitness as f64 / m as f64).max(1.0);
+        let scaled: Vec<Vec<usize>> = self.problem.data.iter()
+            .map(|group| group.iter().map(|item| (item.gain.max(0) as f64 / scale).floor() as usize).collect())
+            .collect();
+        let width = (dantzig_bound / scale).floor() as usize + 1;
+
+        let required = (m * width).div_ceil(32) * 8 + width * 8;
+        if required > self.memory_budget {
+            return Err(SolverError::MemoryBudgetExceeded { required, budget: self.memory_budget });
+        }
+        debug!("FPTAS scale: {} profit columns: {}", scale, width);
+
+        // lightest cost reaching every scaled profit
+        let mut lightest = vec![i64::MAX; width];
+        lightest[0] = 0;
+        let mut choices = ChoiceTable::new(m, width);
+        for (i, group) in self.problem.data.iter().enumerate() {
+            if self.cancellation.is_cancelled() {
+                return Err(SolverError::Cancelled);
+            }
+            for q in (0..width).rev() {
+                let mut cost = lightest[q];
+                let mut choice = 0;
+                for (k, item) in group.iter().enumerate() {
+                    let profit = scaled[i][k];
+                    if item.cost > capacity || profit > q || lightest[q - profit] == i64::MAX {
+                        continue;
+                    }
+                    let candidate = lightest[q - profit] + item.cost;
+                    if candidate < cost {
+                        cost = candidate;
+                        choice = k + 1;
+                    }
+                }
+                if choice != 0 {
+                    lightest[q] = cost;
+                    choices.set(i, q, choice);
+                }
+            }
+        }
+
+        let mut q = (0..width).rev().find(|q| lightest[*q] <= capacity).unwrap_or(0);
+        let mut genes = vec![0; m];
+        for i in (0..m).rev() {
+            let choice = choices.get(i, q);
+            if choice != 0 {
+                genes[i] = choice;
+                q -= scaled[i][choice - 1];
+            }
+        }
+
+        let mut solution = Solution::from_choices(&self.problem, genes, 0);
+        if lower_bound.fitness > solution.fitness {
+            solution = lower_bound;
+        }
+        let upper_bound = dantzig_bound.min(solution.fitness as f64 / (1.0 - self.epsilon));
+        let statistics = FptasStatistics { epsilon: self.epsilon, scale, upper_bound, best: solution.fitness };
+        info!("FPTAS solution: {} is at least {} of the optimum, upper bound: {}", solution.fitness, 1.0 - self.epsilon, upper_bound);
+        Ok((solution, statistics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dp::DynamicProgrammingSolver;
+    use crate::structure::{make_item, make_problem};
+    use crate::utils::make_rng;
+    use rand::Rng;
+
+    fn make_random_problem(seed: u64, size: usize) -> Problem {
+        let mut rng = make_rng(seed);
+        let mut total_cost = 0;
+        let data: Vec<_> = (0..size).map(|_| {
+            let (first_cost, second_cost) = (rng.gen_range(10..1000), rng.gen_range(10..1000));
+            let (first_gain, second_gain) = (rng.gen_range(10..1000), rng.gen_range(10..1000));
+            let third_cost = first_cost + second_cost - rng.gen_range(1..10);
+            total_cost += third_cost;
+            vec![
+                make_item(first_gain, first_cost),
+                make_item(second_gain, second_cost),
+                make_item(first_gain + second_gain, third_cost),
+            ]
+        }).collect();
+        make_problem((total_cost / 3) as u32, data, size as i32)
+    }
+
+    #[test]
+    fn test_solve_is_within_epsilon_of_optimum() {
+        for (seed, epsilon) in [(0, 0.5), (1, 0.2), (2, 0.1), (3, 0.05)] {
+            let problem = make_random_problem(seed, 40);
+            let optimum = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap().fitness;
+            let (solution, statistics) = FptasSolver::new(problem.clone(), epsilon).solve().unwrap();
+
+            assert!(solution.fitness as f64 >= (1.0 - epsilon) * optimum as f64);
+            assert!(solution.cost <= problem.capacity as i64);
+            assert!(statistics.upper_bound >= optimum as f64);
+        }
+    }
+
+    #[test]
+    fn test_lower_bound_is_at_least_half_the_dantzig_bound() {
+        for seed in 0..8 {
+            let problem = make_random_problem(seed, 40);
+            let (dantzig_bound, prefix) = ProblemPreprocessor::new(&problem).dantzig_greedy();
+            let lower_bound = FptasSolver::new(problem.clone(), 0.1).lower_bound(prefix);
+            assert!(2.0 * lower_bound.fitness as f64 >= dantzig_bound);
+            assert!(lower_bound.cost <= problem.capacity as i64);
+        }
+    }
+
+    #[test]
+    fn test_solve_rejects_invalid_epsilon() {
+        let problem = make_problem(10, vec![vec![make_item(1, 1), make_item(2, 2), make_item(3, 3)]], 1);
+        for epsilon in [0.0, 1.0, -0.5, f64::NAN] {
+            assert!(matches!(FptasSolver::new(problem.clone(), epsilon).solve(), Err(SolverError::InvalidEpsilon(_))));
+        }
+    }
+
+    #[test]
+    fn test_solve_refuses_over_budget() {
+        let problem = make_random_problem(4, 40);
+        let result = FptasSolver::new(problem, 0.01).memory_budget(1024).solve();
+        assert!(matches!(result, Err(SolverError::MemoryBudgetExceeded { budget: 1024, .. })));
+    }
+}
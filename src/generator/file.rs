@@ -0,0 +1,73 @@
+use crate::error::SolverError;
+use crate::generator::InitializationStrategy;
+use crate::parser::utils::read_lines;
+use crate::structure::chromosome::Chromosome;
+use crate::structure::seeding_mix::SeedingStrategy;
+
+/// Individuals loaded from a file of choice vectors, one individual per line and one value in
+/// 0..=3 per group, separated by spaces or commas. Individuals are returned in file order and
+/// the file is cycled when more individuals are requested than it contains.
+pub struct FileChromosomeGenerator {
+    chromosomes: Vec<Chromosome>,
+    next: usize,
+}
+
+impl FileChromosomeGenerator {
+    pub fn new(file_name: &str, size: usize) -> Result<Self, SolverError> {
+        let invalid = |reason: String| SolverError::InvalidSeedingFile(reason);
+        let lines = read_lines(&file_name).map_err(|why| invalid(format!("couldn't read {}: {}", file_name, why)))?;
+        let mut chromosomes = vec![];
+        for (number, line) in lines.enumerate() {
+            let line = line.map_err(|why| invalid(format!("couldn't read {}: {}", file_name, why)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let chromosome = FileChromosomeGenerator::parse_line(&line, size)
+                .map_err(|why| invalid(format!("invalid individual at {}:{}: {}", file_name, number + 1, why)))?;
+            chromosomes.push(chromosome);
+        }
+        if chromosomes.is_empty() {
+            return Err(invalid(format!("no individuals found in {}", file_name)));
+        }
+        Ok(FileChromosomeGenerator { chromosomes, next: 0 })
+    }
+
+    fn parse_line(line: &str, size: usize) -> Result<Chromosome, String> {
+        let genes = line.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| match token.parse::<u8>() {
+                Ok(gene) if gene <= 3 => Ok(gene),
+                _ => Err(format!("'{}' is not a choice in 0..=3", token)),
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        if genes.len() != size {
+            return Err(format!("expected {} choices, found {}", size, genes.len()));
+        }
+        Ok(Chromosome::init_chromosome(genes))
+    }
+}
+
+impl InitializationStrategy for FileChromosomeGenerator {
+    fn name(&self) -> &'static str {
+        SeedingStrategy::File.name()
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        let chromosome = self.chromosomes[self.next].clone();
+        self.next = (self.next + 1) % self.chromosomes.len();
+        chromosome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let chromosome = FileChromosomeGenerator::parse_line("0 3,1  2", 4).unwrap();
+        assert_eq!(chromosome.genes, vec![0, 3, 1, 2]);
+        assert!(FileChromosomeGenerator::parse_line("0 4 1 2", 4).is_err());
+        assert!(FileChromosomeGenerator::parse_line("0 1 2", 4).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::generator::greedy::GreedyChromosomeGenerator;
+use crate::generator::InitializationStrategy;
+use crate::structure::chromosome::Chromosome;
+use crate::structure::problem::Problem;
+use crate::structure::seeding_mix::SeedingStrategy;
+use crate::utils::make_rng;
+use rand::prelude::SmallRng;
+use rand::Rng;
+
+/// GRASP construction: at each step a random item is picked from the restricted candidate
+/// list, made of the `rcl_size` best ratio items that still fit and whose group is empty.
+pub struct GraspChromosomeGenerator<'a> {
+    problem: &'a Problem,
+    rng: SmallRng,
+    sorted: Vec<(usize, usize)>,
+    rcl_size: usize,
+}
+
+impl<'a> GraspChromosomeGenerator<'a> {
+    pub fn new(problem: &'a Problem, seed: u64, rcl_size: usize) -> Self {
+        GraspChromosomeGenerator {
+            problem,
+            rng: make_rng(seed),
+            sorted: GreedyChromosomeGenerator::sorted_by_ratio(problem),
+            rcl_size: rcl_size.max(1),
+        }
+    }
+}
+
+impl InitializationStrategy for GraspChromosomeGenerator<'_> {
+    fn name(&self) -> &'static str {
+        SeedingStrategy::Grasp.name()
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        let mut genes = vec![0; self.problem.size as usize];
+        let mut remaining_capacity = self.problem.capacity as i64;
+        let mut candidates = self.sorted.iter();
+        let mut rcl: Vec<(usize, usize)> = Vec::with_capacity(self.rcl_size);
+
+        loop {
+            // remaining capacity only decreases, items dropped here never become feasible again
+            rcl.retain(|(i, k)| genes[*i] == 0 && self.problem.data[*i][*k].cost <= remaining_capacity);
+            while rcl.len() < self.rcl_size {
+                match candidates.next() {
+                    Some(&(i, k)) => {
+                        if genes[i] == 0 && self.problem.data[i][k].cost <= remaining_capacity {
+                            rcl.push((i, k));
+                        }
+                    }
+                    None => break,
+                }
+            }
+            if rcl.is_empty() {
+                break;
+            }
+            let (i, k) = rcl.swap_remove(self.rng.gen_range(0..rcl.len()));
+            genes[i] = k as u8 + 1;
+            remaining_capacity -= self.problem.data[i][k].cost;
+        }
+
+        Chromosome::init_chromosome(genes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    #[test]
+    fn test_grasp_builds_feasible_chromosomes() {
+        let problem = make_problem(30, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+            vec![make_item(20, 1), make_item(35, 35), make_item(55, 35)],
+        ], 3);
+        let mut generator = GraspChromosomeGenerator::new(&problem, 0, 3);
+        for _ in 0..20 {
+            let chromosome = generator.generate_chromosome();
+            let cost: i64 = chromosome.genes.iter().enumerate()
+                .filter(|(_, g)| **g != 0)
+                .map(|(i, g)| problem.data[i][*g as usize - 1].cost)
+                .sum();
+            assert!(cost <= problem.capacity as i64);
+        }
+    }
+
+    #[test]
+    fn test_grasp_with_unit_rcl_is_plain_greedy() {
+        let problem = make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+        ], 2);
+        let chromosome = GraspChromosomeGenerator::new(&problem, 7, 1).generate_chromosome();
+        assert_eq!(chromosome.genes, vec![1, 3]);
+    }
+}
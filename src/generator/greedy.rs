@@ -0,0 +1,83 @@
+use crate::generator::InitializationStrategy;
+use crate::structure::chromosome::Chromosome;
+use crate::structure::problem::Problem;
+use crate::structure::seeding_mix::SeedingStrategy;
+
+/// Ratio greedy in the style of SecGreedy-DKP.
+///
+/// Items are scanned by decreasing gain/cost ratio. An item is taken when its group is still
+/// empty and it fits, or when it improves the gain of the item already taken in its group and the
+/// cost difference fits in the remaining capacity.
+pub struct GreedyChromosomeGenerator {
+    chromosome: Chromosome,
+}
+
+impl GreedyChromosomeGenerator {
+    pub fn new(problem: &Problem) -> Self {
+        GreedyChromosomeGenerator {
+            chromosome: GreedyChromosomeGenerator::sec_greedy(problem),
+        }
+    }
+
+    pub(crate) fn sorted_by_ratio(problem: &Problem) -> Vec<(usize, usize)> {
+        let mut items: Vec<(usize, usize)> = problem.data.iter().enumerate()
+            .flat_map(|(i, group)| (0..group.len()).map(move |k| (i, k)))
+            .collect();
+        items.sort_by(|a, b| {
+            let first = &problem.data[a.0][a.1];
+            let second = &problem.data[b.0][b.1];
+            first.rate.total_cmp(&second.rate).then(second.gain.cmp(&first.gain))
+        });
+        items
+    }
+
+    fn sec_greedy(problem: &Problem) -> Chromosome {
+        let mut genes = vec![0; problem.size as usize];
+        let mut remaining_capacity = problem.capacity as i64;
+
+        for (i, k) in GreedyChromosomeGenerator::sorted_by_ratio(problem) {
+            let item = &problem.data[i][k];
+            if genes[i] == 0 {
+                if item.cost <= remaining_capacity {
+                    genes[i] = k as u8 + 1;
+                    remaining_capacity -= item.cost;
+                }
+            } else {
+                let current = &problem.data[i][genes[i] as usize - 1];
+                if item.gain > current.gain && item.cost - current.cost <= remaining_capacity {
+                    remaining_capacity -= item.cost - current.cost;
+                    genes[i] = k as u8 + 1;
+                }
+            }
+        }
+
+        Chromosome::init_chromosome(genes)
+    }
+}
+
+impl InitializationStrategy for GreedyChromosomeGenerator {
+    fn name(&self) -> &'static str {
+        SeedingStrategy::Greedy.name()
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        self.chromosome.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    #[test]
+    fn test_sec_greedy_fills_capacity_by_ratio() {
+        let problem = make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+        ], 2);
+        let chromosome = GreedyChromosomeGenerator::new(&problem).generate_chromosome();
+        // (1, 3) has the best ratio and takes 15, (0, 1) is the only item fitting the remaining 5
+        assert_eq!(chromosome.genes, vec![1, 3]);
+    }
+}
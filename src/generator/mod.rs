@@ -1,119 +1,269 @@
+use crate::error::SolverError;
+use crate::preprocessing::PreprocessingResult;
 use crate::structure::chromosome::Chromosome;
+use crate::structure::configuration::Configuration;
 use crate::structure::fix_result::UBFixResult;
+use crate::structure::lagrangian_result::LagrangianResult;
 use crate::structure::problem::Problem;
 use crate::structure::relaxation_result::LPRelaxationResult;
+use crate::structure::seeding_mix::SeedingStrategy;
 use crate::utils::make_rng;
 use rand::prelude::SmallRng;
 use rand::Rng;
 
+pub(crate) use file::FileChromosomeGenerator;
+pub(crate) use grasp::GraspChromosomeGenerator;
+pub(crate) use greedy::GreedyChromosomeGenerator;
+pub(crate) use opposition::OppositionChromosomeGenerator;
+
+mod file;
+mod grasp;
+mod greedy;
+mod opposition;
+
+/// Source of chromosomes for the initial population.
+pub trait InitializationStrategy {
+    fn name(&self) -> &'static str;
+    fn generate_chromosome(&mut self) -> Chromosome;
+}
+
+pub(crate) fn make_strategy<'a>(
+    strategy: SeedingStrategy,
+    problem: &'a Problem,
+    configuration: &dyn Configuration,
+    preprocessing_result: &PreprocessingResult,
+) -> Result<Box<dyn InitializationStrategy + 'a>, SolverError> {
+    let seed = configuration.get_seed();
+    let enhanced = |mode| EnhancedChromosomeGenerator::new(
+        problem,
+        seed,
+        &preprocessing_result.relaxation_result,
+        &preprocessing_result.ub_fix_result,
+        mode,
+    );
+    Ok(match strategy {
+        SeedingStrategy::UbFixBest => Box::new(UBFixBestGenerator::new(&preprocessing_result.ub_fix_result)),
+        SeedingStrategy::F0 => Box::new(enhanced(EnhancedMode::F0)),
+        SeedingStrategy::F1 => Box::new(enhanced(EnhancedMode::F1)),
+        SeedingStrategy::F0AndF1 => Box::new(enhanced(EnhancedMode::F0AndF1)),
+        SeedingStrategy::Lagrangian => Box::new(LagrangianBestGenerator::new(&preprocessing_result.lagrangian_result)),
+        SeedingStrategy::Random => Box::new(RandomChromosomeGenerator::new(problem, seed)),
+        SeedingStrategy::Greedy => Box::new(GreedyChromosomeGenerator::new(problem)),
+        SeedingStrategy::Grasp => Box::new(GraspChromosomeGenerator::new(problem, seed, configuration.get_grasp_rcl_size())),
+        SeedingStrategy::Opposition => Box::new(OppositionChromosomeGenerator::new(problem, seed)),
+        SeedingStrategy::File => {
+            let path = configuration.get_seeding_file().ok_or(SolverError::MissingSeedingFile)?;
+            Box::new(FileChromosomeGenerator::new(&path, problem.size as usize)?)
+        }
+    })
+}
+
 pub struct RandomChromosomeGenerator {
-    problem: Problem,
+    size: usize,
     rng: SmallRng,
 }
 
 impl RandomChromosomeGenerator {
-    pub fn new(problem: Problem, seed: u64) -> Self {
+    pub fn new(problem: &Problem, seed: u64) -> Self {
         RandomChromosomeGenerator {
-            problem,
+            size: problem.size as usize,
             rng: make_rng(seed),
         }
     }
 
-    pub(crate) fn generate_chromosome(&mut self) -> Chromosome {
-        let mut genes = vec![];
-        for _ in 0..self.problem.size {
-            genes.push(self.rng.gen_range(0..4));
+}
+
+impl InitializationStrategy for RandomChromosomeGenerator {
+    fn name(&self) -> &'static str {
+        SeedingStrategy::Random.name()
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        // drawn as usize, a seed gives the same chromosomes whatever the width of the genes
+        let genes = (0..self.size).map(|_| self.rng.gen_range(0..4usize) as u8).collect();
+        Chromosome::init_chromosome(genes)
+    }
+}
+
+/// Always returns the best solution found by the UB-fix preprocessing.
+pub struct UBFixBestGenerator {
+    chromosome: Chromosome,
+}
+
+impl UBFixBestGenerator {
+    pub fn new(ub_fix_result: &UBFixResult) -> Self {
+        UBFixBestGenerator {
+            chromosome: UBFixBestGenerator::map_preprocessed_item_to_chromosome(&ub_fix_result.x_best),
+        }
+    }
+
+    fn map_preprocessed_item_to_chromosome(best_preprocess: &[[f64; 3]]) -> Chromosome {
+        let genes = best_preprocess.iter().map(|x| {
+            let mut value = 3;
+            for v in x.iter().rev() {
+                if *v == 1.0 {
+                    break;
+                }
+                value -= 1;
+            }
+            value
+        }).collect();
+        Chromosome::init_chromosome(genes)
+    }
+}
+
+impl InitializationStrategy for UBFixBestGenerator {
+    fn name(&self) -> &'static str {
+        SeedingStrategy::UbFixBest.name()
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        self.chromosome.clone()
+    }
+}
+
+/// Always returns the best solution found by the Lagrangian heuristic.
+pub struct LagrangianBestGenerator {
+    chromosome: Chromosome,
+}
+
+impl LagrangianBestGenerator {
+    pub fn new(lagrangian_result: &LagrangianResult) -> Self {
+        LagrangianBestGenerator {
+            chromosome: Chromosome::init_chromosome(lagrangian_result.choices.iter().map(|choice| *choice as u8).collect()),
         }
-        Chromosome { genes, fitness: 0, age: 0 }
     }
 }
 
+impl InitializationStrategy for LagrangianBestGenerator {
+    fn name(&self) -> &'static str {
+        SeedingStrategy::Lagrangian.name()
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        self.chromosome.clone()
+    }
+}
+
+/// Preprocessing information honoured by an [`EnhancedChromosomeGenerator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnhancedMode {
+    F0,
+    F1,
+    F0AndF1,
+}
 
 pub struct EnhancedChromosomeGenerator {
-    problem: Problem,
     rng: SmallRng,
-    relaxation_result: LPRelaxationResult,
-    ub_fix_result: UBFixResult,
+    // items fixed to 0 by the LP relaxation as a bit per item, and groups fixed by the UB-fix
+    excluded: Vec<u8>,
+    fixed: Vec<bool>,
+    mode: EnhancedMode,
 }
 
 impl EnhancedChromosomeGenerator {
     pub fn new(
-        problem: Problem,
+        problem: &Problem,
         seed: u64,
-        relaxation_result: LPRelaxationResult,
-        ub_fix_result: UBFixResult,
+        relaxation_result: &LPRelaxationResult,
+        ub_fix_result: &UBFixResult,
+        mode: EnhancedMode,
     ) -> Self {
+        let size = problem.size as usize;
+        let mut excluded = vec![0; size];
+        for (group, item) in &relaxation_result.f_0 {
+            excluded[*group] |= 1 << item;
+        }
+        let mut fixed = vec![false; size];
+        for (group, _) in ub_fix_result.f_1.iter().filter(|(_, item)| *item == 1) {
+            fixed[*group] = true;
+        }
         EnhancedChromosomeGenerator {
-            problem,
             rng: make_rng(seed),
-            relaxation_result,
-            ub_fix_result,
+            excluded,
+            fixed,
+            mode,
         }
     }
 
-    pub(crate) fn generate_chromosome_f0_and_f1(&mut self) -> Chromosome {
-        let mut genes = vec![];
-        for i in 0..self.problem.size {
-            let mut gene = 0;
-            if !self.ub_fix_result.f_1.contains(&(i as usize, 1)) {
-                gene = self.rng.gen_range(0..4);
-                if gene == 1 && self.relaxation_result.f_0.contains(&(i as usize, 0)) {
-                    gene = 2;
-                }
-                if gene == 2 && self.relaxation_result.f_0.contains(&(i as usize, 1)) {
-                    gene = 3;
-                }
-                if gene == 3 && self.relaxation_result.f_0.contains(&(i as usize, 2)) {
-                    gene = 0;
-                }
+    // steps a forbidden item up to the next option, the third item wrapping to the empty option
+    fn skip_excluded(excluded: u8, mut gene: usize) -> u8 {
+        for item in 0..3 {
+            if gene == item + 1 && excluded & (1 << item) != 0 {
+                gene = (gene + 1) % 4;
             }
-            genes.push(gene);
         }
+        gene as u8
+    }
 
-        Chromosome {
-            age: 0,
-            genes,
-            fitness: 0,
-        }
+    pub(crate) fn generate_chromosome_f0_and_f1(&mut self) -> Chromosome {
+        let genes = (0..self.excluded.len()).map(|i| {
+            // groups fixed by the UB-fix take their third item
+            if self.fixed[i] {
+                return 3;
+            }
+            let gene = self.rng.gen_range(0..4);
+            EnhancedChromosomeGenerator::skip_excluded(self.excluded[i], gene)
+        }).collect();
+
+        Chromosome::init_chromosome(genes)
     }
 
     pub(crate) fn generate_chromosome_f0(&mut self) -> Chromosome {
-        let mut genes = vec![];
-        for i in 0..self.problem.size {
-            let mut gene = self.rng.gen_range(0..4);
-            if gene == 1 && self.relaxation_result.f_0.contains(&(i as usize, 0)) {
-                gene = 2;
-            }
-            if gene == 2 && self.relaxation_result.f_0.contains(&(i as usize, 1)) {
-                gene = 3;
-            }
-            if gene == 3 && self.relaxation_result.f_0.contains(&(i as usize, 2)) {
-                gene = 0;
-            }
-            genes.push(gene);
-        }
+        let genes = (0..self.excluded.len()).map(|i| {
+            let gene = self.rng.gen_range(0..4);
+            EnhancedChromosomeGenerator::skip_excluded(self.excluded[i], gene)
+        }).collect();
 
-        Chromosome {
-            age: 0,
-            genes,
-            fitness: 0,
-        }
+        Chromosome::init_chromosome(genes)
     }
 
     pub(crate) fn generate_chromosome_f1(&mut self) -> Chromosome {
-        let mut genes = vec![];
-        for i in 0..self.problem.size {
-            let mut gene = 0;
-            if !self.ub_fix_result.f_1.contains(&(i as usize, 1)) {
-                gene = self.rng.gen_range(0..4);
+        let genes = (0..self.fixed.len()).map(|i| {
+            // groups fixed by the UB-fix take their third item
+            if self.fixed[i] {
+                return 3;
             }
-            genes.push(gene);
+            self.rng.gen_range(0..4usize) as u8
+        }).collect();
+
+        Chromosome::init_chromosome(genes)
+    }
+}
+
+impl InitializationStrategy for EnhancedChromosomeGenerator {
+    fn name(&self) -> &'static str {
+        match self.mode {
+            EnhancedMode::F0 => SeedingStrategy::F0.name(),
+            EnhancedMode::F1 => SeedingStrategy::F1.name(),
+            EnhancedMode::F0AndF1 => SeedingStrategy::F0AndF1.name(),
+        }
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        match self.mode {
+            EnhancedMode::F0 => self.generate_chromosome_f0(),
+            EnhancedMode::F1 => self.generate_chromosome_f1(),
+            EnhancedMode::F0AndF1 => self.generate_chromosome_f0_and_f1(),
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
 
-        Chromosome {
-            age: 0,
-            genes,
-            fitness: 0,
+    #[test]
+    fn test_fixed_groups_take_their_third_item() {
+        let group = vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)];
+        let problem = make_problem(30, vec![group.clone(), group.clone(), group], 3);
+        let ub_fix_result = UBFixResult::new(vec![(1, 1)], vec![], 0.0);
+        for mode in [EnhancedMode::F1, EnhancedMode::F0AndF1] {
+            let mut generator = EnhancedChromosomeGenerator::new(&problem, 3, &LPRelaxationResult::empty(), &ub_fix_result, mode);
+            let chromosomes: Vec<_> = (0..20).map(|_| generator.generate_chromosome()).collect();
+            assert!(chromosomes.iter().all(|chromosome| chromosome.genes()[1] == 3));
+            assert!(chromosomes.iter().any(|chromosome| chromosome.genes()[0] != 3));
         }
     }
 }
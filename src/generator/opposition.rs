@@ -0,0 +1,62 @@
+use crate::generator::InitializationStrategy;
+use crate::structure::chromosome::Chromosome;
+use crate::structure::problem::Problem;
+use crate::structure::seeding_mix::SeedingStrategy;
+use crate::utils::make_rng;
+use rand::prelude::SmallRng;
+use rand::Rng;
+
+/// Opposition-based learning: random chromosomes are produced together with their opposite,
+/// where option k of a group is mapped to option 3 - k (empty <-> third item, first <-> second).
+pub struct OppositionChromosomeGenerator {
+    size: usize,
+    rng: SmallRng,
+    opposite: Option<Chromosome>,
+}
+
+impl OppositionChromosomeGenerator {
+    pub fn new(problem: &Problem, seed: u64) -> Self {
+        OppositionChromosomeGenerator {
+            size: problem.size as usize,
+            rng: make_rng(seed),
+            opposite: None,
+        }
+    }
+
+    pub(crate) fn opposite_of(chromosome: &Chromosome) -> Chromosome {
+        Chromosome::init_chromosome(chromosome.genes.iter().map(|g| 3 - g).collect())
+    }
+}
+
+impl InitializationStrategy for OppositionChromosomeGenerator {
+    fn name(&self) -> &'static str {
+        SeedingStrategy::Opposition.name()
+    }
+
+    fn generate_chromosome(&mut self) -> Chromosome {
+        if let Some(opposite) = self.opposite.take() {
+            return opposite;
+        }
+        let genes = (0..self.size).map(|_| self.rng.gen_range(0..4usize) as u8).collect();
+        let chromosome = Chromosome::init_chromosome(genes);
+        self.opposite = Some(OppositionChromosomeGenerator::opposite_of(&chromosome));
+        chromosome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    #[test]
+    fn test_opposition_alternates_chromosome_and_opposite() {
+        let problem = make_problem(10, vec![vec![make_item(1, 1), make_item(2, 2), make_item(3, 3)]; 6], 6);
+        let mut generator = OppositionChromosomeGenerator::new(&problem, 3);
+        let chromosome = generator.generate_chromosome();
+        let opposite = generator.generate_chromosome();
+        for (g, o) in chromosome.genes.iter().zip(&opposite.genes) {
+            assert_eq!(g + o, 3);
+        }
+    }
+}
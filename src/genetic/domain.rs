@@ -0,0 +1,165 @@
+use crate::preprocessing::PreprocessingResult;
+use crate::reduction::ProblemReduction;
+use crate::structure::chromosome::Chromosome;
+use crate::structure::problem::Problem;
+use rand::rngs::SmallRng;
+use rand::Rng;
+
+const ALL_OPTIONS: u8 = 0b1111;
+
+// share of the mutations drawing among the preferred options of the group
+const PREFERRED_SHARE: f64 = 0.9;
+
+/// Options the genetic operators may give to each group.
+///
+/// The fixings that hold for every solution improving on the UB-fix one restrict the domain:
+/// the groups fixed by the UB-fix only take their third item, the groups fixed by the
+/// reduced-cost fixing only take their item and the items it fixes to 0 are never taken. The
+/// UB-fix solution stays the answer when the search does not improve on it. LP-dominated items
+/// (`f_0`) are only a bias of the mutation, the LP dominance does not hold for the integer
+/// problem. Bit `k` of a group mask allows, or prefers, option `k`.
+#[derive(Debug, Clone)]
+pub(crate) struct GeneDomain {
+    allowed: Vec<u8>,
+    preferred: Vec<u8>,
+}
+
+impl GeneDomain {
+    /// Domain allowing every option, the genetic algorithm without preprocessing.
+    pub(crate) fn full(groups: usize) -> Self {
+        GeneDomain { allowed: vec![ALL_OPTIONS; groups], preferred: vec![ALL_OPTIONS; groups] }
+    }
+
+    pub(crate) fn new(problem: &Problem, preprocessing_result: &PreprocessingResult) -> Self {
+        let m = problem.data.len();
+        let mut domain = GeneDomain::full(m);
+        if preprocessing_result.relaxation_result.x_up.len() != m {
+            return domain;
+        }
+        // the UB-fix relies on the dominance rules, which expect items ordered by cost
+        if ProblemReduction::is_ordered(problem) {
+            for (group, _) in preprocessing_result.ub_fix_result.f_1.iter().filter(|(_, item)| *item == 1) {
+                domain.allowed[*group] = 1 << 3;
+            }
+        }
+        let reduced_cost_result = &preprocessing_result.reduced_cost_result;
+        for (group, item) in &reduced_cost_result.f_1 {
+            domain.allowed[*group] = 1 << (item + 1);
+        }
+        for (group, item) in &reduced_cost_result.f_0 {
+            domain.allowed[*group] &= !(1 << (item + 1));
+        }
+        // conflicting fixings mean no solution beats the UB-fix one, such a group is left empty
+        for mask in domain.allowed.iter_mut().filter(|mask| **mask == 0) {
+            *mask = 1;
+        }
+        for (group, preferred) in domain.preferred.iter_mut().enumerate() {
+            *preferred = domain.allowed[group];
+        }
+        for (group, item) in &preprocessing_result.relaxation_result.f_0 {
+            domain.preferred[*group] &= !(1 << (item + 1));
+        }
+        domain
+    }
+
+    pub(crate) fn allows(&self, group: usize, option: usize) -> bool {
+        self.allowed[group] & (1 << option) != 0
+    }
+
+    /// Whether the fixings forbid any option.
+    pub(crate) fn is_constrained(&self) -> bool {
+        self.allowed.iter().any(|mask| *mask != ALL_OPTIONS)
+    }
+
+    /// Highest allowed option of `group` below `option`.
+    pub(crate) fn lower_option(&self, group: usize, option: usize) -> Option<usize> {
+        (0..option).rev().find(|lower| self.allows(group, *lower))
+    }
+
+    /// Replaces the forbidden options of the chromosome by the closest allowed one below them,
+    /// above them when none is left below, keeping its totals up to date.
+    pub(crate) fn restrict(&self, problem: &Problem, chromosome: &mut Chromosome) {
+        for group in 0..chromosome.genes.len() {
+            let option = chromosome.genes[group] as usize;
+            if self.allows(group, option) {
+                continue;
+            }
+            let allowed = self.lower_option(group, option).or_else(|| (option + 1..4).find(|upper| self.allows(group, *upper)));
+            chromosome.set_gene(problem, group, allowed.unwrap() as u8);
+        }
+    }
+
+    /// Random allowed option of `group`, drawn among the preferred ones most of the time and
+    /// among all allowed ones otherwise.
+    pub(crate) fn random_option(&self, rng: &mut SmallRng, group: usize) -> usize {
+        let (allowed, preferred) = (self.allowed[group], self.preferred[group]);
+        let mask = if preferred != 0 && preferred != allowed && rng.gen_bool(PREFERRED_SHARE) { preferred } else { allowed };
+        if mask == ALL_OPTIONS {
+            return rng.gen_range(0..4);
+        }
+        let nth = rng.gen_range(0..mask.count_ones());
+        (0..4).filter(|option| mask & (1 << option) != 0).nth(nth as usize).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessing::ProblemPreprocessor;
+    use crate::structure::fix_result::UBFixResult;
+    use crate::structure::reduced_cost_result::ReducedCostResult;
+    use crate::structure::{make_item, make_problem};
+    use crate::utils;
+
+    #[test]
+    fn test_domain_prefers_options_kept_by_preprocessing() {
+        let problem = make_problem(30, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(2, 7), make_item(25, 12), make_item(45, 15)],
+            vec![make_item(11, 3), make_item(22, 15), make_item(33, 16)],
+        ], 3);
+        let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+        let domain = GeneDomain::new(&problem, &preprocessing);
+        assert!(!preprocessing.relaxation_result.f_0.is_empty());
+        for (group, item) in &preprocessing.relaxation_result.f_0 {
+            assert_eq!(domain.preferred[*group] & (1 << (item + 1)), 0);
+        }
+
+        // a dominated option is drawn less often, but it is still drawn
+        let (group, item) = preprocessing.relaxation_result.f_0[0];
+        assert!(domain.allows(group, item + 1));
+        let mut rng = utils::make_rng(1);
+        let mut draws = [0; 4];
+        for _ in 0..4000 {
+            draws[domain.random_option(&mut rng, group)] += 1;
+        }
+        assert!(draws.iter().all(|count| *count > 0));
+        assert!(draws[item + 1] < 1000);
+    }
+
+    #[test]
+    fn test_domain_keeps_fixed_groups_to_their_options() {
+        let group = vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)];
+        let problem = make_problem(30, vec![group.clone(), group.clone(), group], 3);
+        let mut preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+        preprocessing.ub_fix_result = UBFixResult::new(vec![(0, 1)], vec![], 0.0);
+        preprocessing.reduced_cost_result = ReducedCostResult::new(vec![(2, 1)], vec![(1, 0)], 0.0);
+        let domain = GeneDomain::new(&problem, &preprocessing);
+        assert!(domain.is_constrained());
+
+        let mut rng = utils::make_rng(1);
+        for _ in 0..100 {
+            assert_eq!(domain.random_option(&mut rng, 0), 3);
+            assert_eq!(domain.random_option(&mut rng, 1), 1);
+            assert_ne!(domain.random_option(&mut rng, 2), 2);
+        }
+
+        let mut chromosome = Chromosome::init_chromosome(vec![1, 3, 2]);
+        chromosome.summarize(&problem);
+        domain.restrict(&problem, &mut chromosome);
+        assert_eq!(chromosome.genes, vec![3, 1, 1]);
+        assert_eq!((chromosome.gain, chromosome.cost), (50, 24));
+        assert_eq!(domain.lower_option(2, 3), Some(1));
+        assert_eq!(domain.lower_option(0, 3), None);
+    }
+}
@@ -1,32 +1,90 @@
-use crate::generator::{EnhancedChromosomeGenerator, RandomChromosomeGenerator};
-use crate::preprocessing::PreprocessingResult;
+use crate::error::SolverError;
+use crate::generator::make_strategy;
+use crate::genetic::domain::GeneDomain;
+use crate::portfolio::SharedIncumbent;
+use crate::preprocessing::{PreprocessingResult, ProblemPreprocessor};
+use crate::solver::Cancellation;
 use crate::structure::chromosome::Chromosome;
 use crate::structure::configuration::Configuration;
+use crate::structure::constraint_handling::ConstraintHandling;
 use crate::structure::problem::Problem;
+use crate::structure::seeding_mix::{SeedingCounts, SeedingStrategy};
 use crate::structure::solution::Solution;
 use crate::utils;
 use log::{debug, info};
 use rand::rngs::SmallRng;
 use rand::Rng;
+use std::collections::VecDeque;
+use std::sync::Arc;
 
-pub trait GeneticAlgorithm<'a> {
-    fn init(problem: Problem, configuration: Box<dyn Configuration>, preprocessing_result: &'a PreprocessingResult) -> Self;
+pub(crate) mod domain;
+pub(crate) mod operators;
+
+// generations whose feasible share drives the adaptive penalty, and the share it aims at
+const ADAPTIVE_WINDOW: usize = 10;
+const TARGET_FEASIBLE_SHARE: f64 = 0.5;
+const ADAPTIVE_FACTOR: f64 = 1.1;
+
+/// Genetic algorithm over chromosomes holding one choice per group.
+///
+/// `init` panics when the initial population can not be seeded, [`SolverBuilder`] reports the
+/// same failures as a [`SolverError`].
+///
+/// [`SolverBuilder`]: crate::SolverBuilder
+///
+/// ```
+/// use discounted_zeroone_knapsack::{GeneticAlgorithm, Item, Problem, SolverBuilder};
+///
+/// let problem = Problem::new(15, vec![vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)]; 3]);
+/// let mut executor = SolverBuilder::new().problem(problem).population_size(10).build().unwrap();
+/// let solution = executor.run();
+/// assert_eq!(solution.fitness(), 30);
+/// ```
+pub trait GeneticAlgorithm {
+    fn init(problem: Arc<Problem>, configuration: Box<dyn Configuration>, preprocessing_result: PreprocessingResult) -> Self;
     fn run(&mut self) -> Solution;
+    fn seeding_counts(&self) -> SeedingCounts;
 }
 
-pub struct KnapsackGeneticAlgorithm<'a> {
+pub struct KnapsackGeneticAlgorithm {
     best: Chromosome,
     remain_no_improved_generations: u8,
     configuration: Box<dyn Configuration>,
     population: Vec<Chromosome>,
-    problem: Problem,
+    problem: Arc<Problem>,
     rng: SmallRng,
     mutation_factor: u16,
-    preprocessing_result: &'a PreprocessingResult,
+    preprocessing_result: PreprocessingResult,
+    seeding_counts: SeedingCounts,
+    cancellation: Cancellation,
+    incumbent: Option<(SharedIncumbent, usize)>,
+    upper_bound: f64,
+    domain: GeneDomain,
+    constraint_handling: ConstraintHandling,
+    penalty: f64,
+    initial_penalty: f64,
+    feasible_shares: VecDeque<f64>,
+    feasible_leader: Option<Chromosome>,
 }
 
-impl<'a> KnapsackGeneticAlgorithm<'a> {
-    pub(crate) fn new(problem: Problem, configuration: Box<dyn Configuration>, preprocessing_result: &'a PreprocessingResult) -> Self {
+impl KnapsackGeneticAlgorithm {
+    pub(crate) fn new(problem: Arc<Problem>, configuration: Box<dyn Configuration>, preprocessing_result: PreprocessingResult) -> Self {
+        // gains are integers, the floor of the bound is still a bound
+        let upper_bound = ProblemPreprocessor::new(&problem).dantzig_bound().floor();
+        let domain = if configuration.is_enhanced_enabled() && configuration.is_domain_constrained() {
+            GeneDomain::new(&problem, &preprocessing_result)
+        } else {
+            GeneDomain::full(problem.data.len())
+        };
+        let constraint_handling = configuration.get_constraint_handling();
+        // an excess cost paid at the best rate of the items never pays off
+        let initial_penalty = match constraint_handling {
+            ConstraintHandling::StaticPenalty(Some(coefficient)) => coefficient,
+            _ => problem.data.iter().flatten()
+                .filter(|item| item.cost > 0)
+                .map(|item| item.gain as f64 / item.cost as f64)
+                .fold(1.0, f64::max),
+        };
         KnapsackGeneticAlgorithm {
             best: Chromosome::init_chromosome(vec![]),
             remain_no_improved_generations: configuration.get_no_upgrade_limit(),
@@ -36,150 +94,237 @@ impl<'a> KnapsackGeneticAlgorithm<'a> {
             problem,
             preprocessing_result,
             configuration,
+            seeding_counts: SeedingCounts::default(),
+            cancellation: Cancellation::new(),
+            incumbent: None,
+            upper_bound,
+            domain,
+            constraint_handling,
+            penalty: initial_penalty,
+            initial_penalty,
+            feasible_shares: VecDeque::with_capacity(ADAPTIVE_WINDOW),
+            feasible_leader: None,
         }
     }
 
-    fn get_chromosome_summary(&self, chromosome: &Chromosome) -> (i64, i64) {
-        let (mut gain, mut cost) = (0, 0);
-        for (i, gene) in chromosome.genes.iter().enumerate() {
-            if *gene != 0 {
-                gain += self.problem.data[i][*gene - 1].gain;
-                cost += self.problem.data[i][*gene - 1].cost;
-            }
-        }
-        (gain, cost)
+    pub fn configuration(&self) -> &dyn Configuration {
+        self.configuration.as_ref()
     }
 
-    fn find_max_rate(&self, chromosome: &Chromosome) -> (usize, usize) {
-        let mut max_rate = 0.0;
-        let mut max_gene = 0;
-        let mut max_value = 1;
-        for (gene, value) in chromosome.genes.iter().enumerate() {
-            if *value != 0 {
-                let curr_rate = self.problem.data[gene][*value - 1].rate;
-                if curr_rate > max_rate {
-                    max_rate = curr_rate;
-                    max_gene = gene;
-                    max_value = *value;
+    /// Stops the evolution at the end of the generation during which `cancellation` is triggered.
+    pub fn set_cancellation(&mut self, cancellation: Cancellation) {
+        self.cancellation = cancellation;
+    }
+
+    /// Dantzig bound of the problem, rounded down.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+
+    /// Whether the best chromosome reaches the upper bound, hence is optimal.
+    pub fn proven_optimal(&self) -> bool {
+        self.best.fitness as f64 >= self.upper_bound
+    }
+
+    /// Whether the best chromosome is within the gap tolerance of the upper bound.
+    pub fn bound_reached(&self) -> bool {
+        self.proven_optimal() || (self.upper_bound - self.best.fitness as f64) / self.upper_bound <= self.configuration.get_gap_tolerance()
+    }
+
+    /// Whether the preprocessing fixings keep some groups away from some of their options.
+    pub fn domain_constrained(&self) -> bool {
+        self.domain.is_constrained()
+    }
+
+    /// Preprocessing used by the enhanced seeding, empty when it has not been run.
+    pub fn preprocessing_result(&self) -> &PreprocessingResult {
+        &self.preprocessing_result
+    }
+
+    /// Current coefficient of the excess cost, only used by the penalty constraint handlings.
+    pub fn penalty(&self) -> f64 {
+        self.penalty
+    }
+
+    pub(crate) fn set_incumbent(&mut self, incumbent: SharedIncumbent, member: usize) {
+        self.incumbent = Some((incumbent, member));
+    }
+
+    // publishes the best chromosome to the portfolio, or injects the incumbent when it is better
+    fn exchange_incumbent(&mut self) {
+        let Some((incumbent, member)) = &self.incumbent else {
+            return;
+        };
+        let leader = self.leader();
+        match incumbent.better_than(leader.fitness) {
+            Some((genes, fitness)) => {
+                self.population.pop();
+                let mut chromosome = Chromosome::init_chromosome(genes);
+                chromosome.summarize(&self.problem);
+                chromosome.set_fitness(fitness);
+                // another member may have taken options this domain forbids
+                if self.domain.is_constrained() {
+                    self.domain.restrict(&self.problem, &mut chromosome);
+                    self.handle_offspring(&mut chromosome);
+                    chromosome.set_fitness(self.fitness_func(chromosome.gain, chromosome.cost));
                 }
+                self.population.insert(0, chromosome);
             }
+            _ => incumbent.offer(&leader.genes, leader.fitness, *member),
         }
-        (max_gene, max_value)
     }
 
-    fn repair_chromosome(&self, chromosome: &Chromosome) -> Chromosome {
-        let capacity = self.problem.capacity;
-        let mut c = chromosome.clone();
-        let mut genes = chromosome.genes.clone();
-        let mut cost = self.get_chromosome_summary(&chromosome).1;
-
-        while cost > capacity as i64 {
-            let (high_rate_gene, value) = self.find_max_rate(&c);
-            cost -= self.problem.data[high_rate_gene][value - 1].cost;
-            if value > 1 {
-                cost += self.problem.data[high_rate_gene][value - 2].cost;
-            }
-            genes[high_rate_gene] = value - 1;
-            c = Chromosome::init_chromosome(genes.clone());
+    // with a penalty the offspring may exceed the capacity, they are left as they are
+    fn handle_offspring(&self, chromosome: &mut Chromosome) {
+        if !self.constraint_handling.is_penalty() {
+            operators::repair_chromosome(&self.problem, &self.domain, chromosome);
         }
-        c
     }
 
-    fn initialize_population(&mut self) {
-        debug!("Initializing population...");
-        let mut generated = self.configuration.get_population_size();
-        let best_preprocess = self.preprocessing_result.ub_fix_result.x_best.clone();
+    /// Best feasible chromosome of the population, the fittest one being repaired when a penalty
+    /// lets it exceed the capacity.
+    fn leader(&self) -> Chromosome {
+        let top = &self.population[0];
+        if !self.constraint_handling.is_penalty() {
+            return top.clone();
+        }
+        let mut repaired = top.clone();
+        if operators::repair_chromosome(&self.problem, &self.domain, &mut repaired) {
+            repaired.fitness = repaired.gain;
+            repaired.age = 0;
+        }
+        match &self.feasible_leader {
+            Some(leader) if leader.fitness >= repaired.fitness => leader.clone(),
+            _ => repaired,
+        }
+    }
 
-        if self.configuration.is_enhanced_enabled() {
-            if !best_preprocess.is_empty() {
-                let mut chromosome = KnapsackGeneticAlgorithm::map_preprocessed_item_to_chromosome(best_preprocess);
-                chromosome = self.repair_chromosome(&chromosome);
-                self.population.push(chromosome);
-                generated -= 1;
-            }
+    // the fixings of the domain only hold for solutions improving on the UB-fix one, which stays
+    // the answer when the search does not beat it
+    fn fallback(&self) -> Option<Chromosome> {
+        if !self.domain.is_constrained() {
+            return None;
+        }
+        let choices = self.preprocessing_result.ub_fix_result.choices();
+        if choices.len() != self.problem.data.len() {
+            return None;
+        }
+        let mut chromosome = Chromosome::init_chromosome(choices.iter().map(|choice| *choice as u8).collect());
+        chromosome.summarize(&self.problem);
+        if chromosome.cost > self.problem.capacity as i64 {
+            return None;
+        }
+        chromosome.set_fitness(chromosome.gain);
+        Some(chromosome)
+    }
+
+    /// Builds the algorithm and its initial population, failing when a seeding strategy can not
+    /// be set up, like a seeding file that can not be read.
+    pub(crate) fn try_init(problem: Arc<Problem>, configuration: Box<dyn Configuration>, preprocessing_result: PreprocessingResult) -> Result<Self, SolverError> {
+        let mut executor = KnapsackGeneticAlgorithm::new(problem, configuration, preprocessing_result);
+        executor.initialize_population()?;
+        Ok(executor)
+    }
 
-            let mut enhanced_gen = EnhancedChromosomeGenerator::new(
-                self.problem.clone(),
-                self.configuration.get_seed(),
-                self.preprocessing_result.relaxation_result.clone(),
-                self.preprocessing_result.ub_fix_result.clone(),
-            );
+    fn initialize_population(&mut self) -> Result<(), SolverError> {
+        debug!("Initializing population...");
+        let population_size = self.configuration.get_population_size();
 
-            while generated > (self.configuration.get_population_size() as f64 * 0.95).floor() as u32 {
-                let mut chromosome = enhanced_gen.generate_chromosome_f0();
-                chromosome = self.repair_chromosome(&chromosome);
-                self.population.push(chromosome);
-                generated -= 1;
+        let mut mix = self.configuration.get_seeding_mix();
+        if !self.configuration.is_enhanced_enabled() {
+            mix = mix.without_preprocessing();
+        } else {
+            if self.preprocessing_result.ub_fix_result.x_best.is_empty() {
+                mix = mix.without(SeedingStrategy::UbFixBest);
             }
-
-            while generated > (self.configuration.get_population_size() as f64 * 0.95).floor() as u32 {
-                let mut chromosome = enhanced_gen.generate_chromosome_f1();
-                chromosome = self.repair_chromosome(&chromosome);
-                self.population.push(chromosome);
-                generated -= 1;
+            if self.preprocessing_result.lagrangian_result.choices.is_empty() {
+                mix = mix.without(SeedingStrategy::Lagrangian);
             }
+        }
+        let counts = mix.allocate(population_size);
+        debug_assert_eq!(counts.total(), population_size);
 
-            while generated > (self.configuration.get_population_size() as f64 * 0.975).floor() as u32 {
-                let mut chromosome = enhanced_gen.generate_chromosome_f0_and_f1();
-                chromosome = self.repair_chromosome(&chromosome);
+        for strategy in SeedingStrategy::ALL {
+            let count = counts.get(strategy);
+            if count == 0 {
+                continue;
+            }
+            let mut generator = make_strategy(strategy, &self.problem, self.configuration.as_ref(), &self.preprocessing_result)?;
+            debug!("Seeding {} chromosomes with strategy {}", count, generator.name());
+            for _ in 0..count {
+                let mut chromosome = generator.generate_chromosome();
+                chromosome.summarize(&self.problem);
+                self.domain.restrict(&self.problem, &mut chromosome);
+                operators::repair_chromosome(&self.problem, &self.domain, &mut chromosome);
                 self.population.push(chromosome);
-                generated -= 1;
             }
         }
-        while generated > 0 {
-            let mut chromosome = RandomChromosomeGenerator::new(self.problem.clone(), self.configuration.get_seed()).generate_chromosome();
-            chromosome = self.repair_chromosome(&chromosome);
-            self.population.push(chromosome);
-            generated -= 1;
-        }
-    }
 
-    fn map_preprocessed_item_to_chromosome(best_preprocess: Vec<[f64; 3]>) -> Chromosome {
-        let genes = best_preprocess.iter().map(|x| {
-            let mut value = 3;
-            for v in x.iter().rev() {
-                if *v == 1.0 {
-                    break;
-                }
-                value -= 1;
-            }
-            value
-        }).collect();
-        Chromosome::init_chromosome(genes)
+        self.seeding_counts = counts;
+        Ok(())
     }
 
-    fn make_solution(&mut self, chromosome: &Chromosome, generations: u32) -> Solution {
-        let mut data = Vec::new();
-        let mut cost = 0;
-        for (gene, value) in chromosome.genes.iter().enumerate() {
-            if *value != 0 {
-                data.push(self.problem.data[gene][*value - 1].clone());
-                cost += self.problem.data[gene][*value - 1].cost;
-            }
-        }
-        Solution::make_solution(data, chromosome.fitness, cost, generations)
-    }
 
-    fn fitness_func(&self, chromosome: &Chromosome) -> i64 {
-        let (gain, cost) = self.get_chromosome_summary(&chromosome);
-        if cost > self.problem.capacity as i64 {
-            return 0;
+    fn fitness_func(&self, gain: i64, cost: i64) -> i64 {
+        let excess = cost - self.problem.capacity as i64;
+        if excess <= 0 {
+            return gain;
+        }
+        match self.constraint_handling {
+            ConstraintHandling::Repair | ConstraintHandling::DeathPenalty => 0,
+            ConstraintHandling::StaticPenalty(_) | ConstraintHandling::AdaptivePenalty => {
+                (gain - (self.penalty * excess as f64).ceil() as i64).max(0)
+            }
         }
-        gain
     }
 
     fn evaluate(&mut self) {
         debug!("Evaluating population...");
-        let fitness: Vec<_> = self.population.iter().map(|c| self.fitness_func(c)).collect();
-        for (i, chromosome) in self.population.iter_mut().enumerate() {
-            chromosome.set_fitness(fitness[i]);
+        let capacity = self.problem.capacity as i64;
+        let evaluated: Vec<_> = self.population.iter().map(|c| {
+            debug_assert_eq!((c.gain, c.cost), operators::summary(&self.problem, &c.genes));
+            (self.fitness_func(c.gain, c.cost), c.cost <= capacity)
+        }).collect();
+        for (chromosome, (fitness, _)) in self.population.iter_mut().zip(&evaluated) {
+            chromosome.set_fitness(*fitness);
             chromosome.increase_age();
         }
-        self.population.sort_by(|a, b| b.fitness.cmp(&a.fitness));
+        if self.constraint_handling.is_penalty() {
+            // a feasible chromosome is not penalized, its fitness is its gain
+            self.feasible_leader = self.population.iter().zip(&evaluated)
+                .filter(|(_, (_, feasible))| *feasible)
+                .map(|(c, _)| c)
+                .max_by_key(|c| c.fitness)
+                .cloned();
+            let feasible = evaluated.iter().filter(|(_, feasible)| *feasible).count();
+            self.adapt_penalty(feasible as f64 / evaluated.len() as f64);
+        }
+        self.population.sort_by_key(|c| std::cmp::Reverse(c.fitness));
+    }
+
+    // raises the coefficient while the recent generations are mostly infeasible, lowers it otherwise
+    fn adapt_penalty(&mut self, feasible_share: f64) {
+        if self.constraint_handling != ConstraintHandling::AdaptivePenalty {
+            return;
+        }
+        if self.feasible_shares.len() == ADAPTIVE_WINDOW {
+            self.feasible_shares.pop_front();
+        }
+        self.feasible_shares.push_back(feasible_share);
+        let mean = self.feasible_shares.iter().sum::<f64>() / self.feasible_shares.len() as f64;
+        self.penalty = if mean < TARGET_FEASIBLE_SHARE {
+            self.penalty * ADAPTIVE_FACTOR
+        } else {
+            self.penalty / ADAPTIVE_FACTOR
+        }.clamp(self.initial_penalty / 100.0, self.initial_penalty * 100.0);
     }
 
     fn roulette_wheel_selection(&mut self) {
         let sum_fitness = self.population.iter().map(|c| c.fitness).sum();
+        // nothing to tell the chromosomes apart, a death penalty can leave none feasible
+        if sum_fitness == 0 {
+            return;
+        }
         self.population = (0..self.population.len()).map(|_| {
             let mut slice = self.rng.gen_range(0..sum_fitness);
             let mut index = 0;
@@ -199,28 +344,39 @@ impl<'a> KnapsackGeneticAlgorithm<'a> {
         self.roulette_wheel_selection();
     }
 
-    fn parent_crossover(&mut self, parent1: &Chromosome, parent2: &Chromosome) -> (Chromosome, Chromosome) {
-        let crossover_point = self.rng.gen_range(0..parent1.genes.len());
+    fn parent_crossover(&self, parent1: &Chromosome, parent2: &Chromosome, crossover_point: usize) -> (Chromosome, Chromosome) {
         let (child1_genes, child2_genes): (Vec<_>, Vec<_>) = parent1.genes.iter().zip(&parent2.genes)
             .enumerate()
             .map(|(i, (g1, g2))| if i < crossover_point { (*g1, *g2) } else { (*g2, *g1) })
             .unzip();
 
+        // the children exchange the genes before the crossover point, the difference of the parents
+        // over them is summed on the shorter side of the point
+        let length = parent1.genes.len();
+        let (gain_shift, cost_shift) = if crossover_point <= length / 2 {
+            operators::difference(&self.problem, &parent1.genes[..crossover_point], &parent2.genes[..crossover_point], 0)
+        } else {
+            let (gain, cost) = operators::difference(&self.problem, &parent1.genes[crossover_point..], &parent2.genes[crossover_point..], crossover_point);
+            (parent1.gain - parent2.gain - gain, parent1.cost - parent2.cost - cost)
+        };
         let mut child1 = Chromosome::init_chromosome(child1_genes);
-        child1 = self.repair_chromosome(&child1);
+        (child1.gain, child1.cost) = (parent2.gain + gain_shift, parent2.cost + cost_shift);
         let mut child2 = Chromosome::init_chromosome(child2_genes);
-        child2 = self.repair_chromosome(&child2);
+        (child2.gain, child2.cost) = (parent1.gain - gain_shift, parent1.cost - cost_shift);
+        self.handle_offspring(&mut child1);
+        self.handle_offspring(&mut child2);
 
         (child1, child2)
     }
 
     fn crossover(&mut self) {
         debug!("Crossover population...");
-        let mut new_population = Vec::new();
+        let mut new_population = Vec::with_capacity(self.population.len());
         for _ in 0..self.population.len() / 2 {
-            let parent1 = self.population[self.rng.gen_range(0..self.population.len())].clone();
-            let parent2 = self.population[self.rng.gen_range(0..self.population.len())].clone();
-            let (child1, child2) = self.parent_crossover(&parent1, &parent2);
+            let parent1 = self.rng.gen_range(0..self.population.len());
+            let parent2 = self.rng.gen_range(0..self.population.len());
+            let crossover_point = self.rng.gen_range(0..self.problem.data.len());
+            let (child1, child2) = self.parent_crossover(&self.population[parent1], &self.population[parent2], crossover_point);
             new_population.push(child1);
             new_population.push(child2);
         }
@@ -231,9 +387,7 @@ impl<'a> KnapsackGeneticAlgorithm<'a> {
         debug!("Mutating population...");
         self.population.iter_mut().for_each(|c| {
             if self.rng.gen_range(0..1000) <= self.mutation_factor {
-                let index = self.rng.gen_range(0..c.genes.len());
-                let gene = self.rng.gen_range(0..4);
-                c.genes[index] = gene;
+                operators::change_option_in(&mut self.rng, &self.problem, &self.domain, c);
                 c.fitness = 0;
                 c.age = 0;
             }
@@ -244,19 +398,23 @@ impl<'a> KnapsackGeneticAlgorithm<'a> {
         if new_chromosome.fitness > self.best.fitness {
             self.best = new_chromosome;
             self.remain_no_improved_generations = self.configuration.get_no_upgrade_limit();
-            return false;
-        }
-        if self.remain_no_improved_generations > 0 {
+        } else if self.remain_no_improved_generations > 0 {
             self.remain_no_improved_generations -= 1;
-            return false;
+        } else {
+            return true;
         }
-        true
+        if self.bound_reached() {
+            info!("Best fitness: {} is within the gap tolerance of the upper bound: {}", self.best.fitness, self.upper_bound);
+            return true;
+        }
+        self.cancellation.is_cancelled()
     }
 
     fn evolve(&mut self) -> (Chromosome, u32) {
         let mut generation: u32 = 0;
         self.evaluate();
-        while !self.check_is_end(self.population.first().cloned().unwrap_or_else(|| panic!("Problem occurs during evolution!"))) {
+        self.exchange_incumbent();
+        while !self.check_is_end(self.leader()) {
             info!("Evolving population generation: {} current best fitness: {}", generation, self.best.fitness);
             self.select();
             self.crossover();
@@ -266,21 +424,32 @@ impl<'a> KnapsackGeneticAlgorithm<'a> {
                 self.mutation_factor -= 1;
             }
             self.evaluate();
+            self.exchange_incumbent();
         }
         (self.best.clone(), generation)
     }
 }
 
-impl<'a> GeneticAlgorithm<'a> for KnapsackGeneticAlgorithm<'a> {
-    fn init(problem: Problem, configuration: Box<dyn Configuration>, preprocessing_result: &'a PreprocessingResult) -> Self {
-        let mut executor = KnapsackGeneticAlgorithm::new(problem, configuration, preprocessing_result);
-        executor.initialize_population();
-        executor
+impl GeneticAlgorithm for KnapsackGeneticAlgorithm {
+    fn init(problem: Arc<Problem>, configuration: Box<dyn Configuration>, preprocessing_result: PreprocessingResult) -> Self {
+        KnapsackGeneticAlgorithm::try_init(problem, configuration, preprocessing_result).unwrap_or_else(|why| panic!("{}", why))
     }
 
     fn run(&mut self) -> Solution {
         info!("Running genetic algorithm for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
-        let (best, generations) = self.evolve();
-        self.make_solution(&best, generations)
+        let (mut best, generations) = self.evolve();
+        // the leaders are feasible, the repair only guards the answer
+        if operators::repair_chromosome(&self.problem, &GeneDomain::full(best.genes.len()), &mut best) {
+            best.fitness = best.gain;
+        }
+        if let Some(fallback) = self.fallback().filter(|fallback| fallback.fitness > best.fitness) {
+            best = fallback;
+            self.best = best.clone();
+        }
+        Solution::from_choices(&self.problem, best.choices(), generations)
+    }
+
+    fn seeding_counts(&self) -> SeedingCounts {
+        self.seeding_counts.clone()
     }
 }
\ No newline at end of file
@@ -0,0 +1,158 @@
+use crate::genetic::domain::GeneDomain;
+use crate::structure::chromosome::{Chromosome, Gene};
+use crate::structure::problem::Problem;
+use rand::rngs::SmallRng;
+use rand::Rng;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// moves and repair rule shared by the genetic algorithm and the single-solution metaheuristics
+
+pub(crate) fn summary<G: Gene>(problem: &Problem, genes: &[G]) -> (i64, i64) {
+    let (mut gain, mut cost) = (0, 0);
+    for (i, gene) in genes.iter().enumerate() {
+        let (g, c) = problem.option_summary(i, gene.option());
+        gain += g;
+        cost += c;
+    }
+    (gain, cost)
+}
+
+/// Steps down the option of the group with the worst cost/gain rate until the genes fit,
+/// returns whether any gene has been changed.
+pub(crate) fn repair<G: Gene>(problem: &Problem, genes: &mut [G]) -> bool {
+    let cost = summary(problem, genes).1;
+    step_down(problem, genes, cost, |_, option| Some(option - 1));
+    cost > problem.capacity as i64
+}
+
+/// Same as [`repair`], stepping down to the options allowed by `domain` only, starting from the
+/// totals cached by the chromosome and updating them.
+pub(crate) fn repair_chromosome(problem: &Problem, domain: &GeneDomain, chromosome: &mut Chromosome) -> bool {
+    if chromosome.cost <= problem.capacity as i64 {
+        return false;
+    }
+    let (gain, cost) = step_down(problem, &mut chromosome.genes, chromosome.cost, |group, option| domain.lower_option(group, option));
+    chromosome.gain += gain;
+    chromosome.cost += cost;
+    true
+}
+
+// steps down the genes of total `cost` until they fit, `lower` giving the option below an option
+// of a group, returns the changes of gain and cost
+fn step_down<G: Gene>(problem: &Problem, genes: &mut [G], mut cost: i64, lower: impl Fn(usize, usize) -> Option<usize>) -> (i64, i64) {
+    let capacity = problem.capacity as i64;
+    let (mut gain_change, mut cost_change) = (0, 0);
+    if cost <= capacity {
+        return (0, 0);
+    }
+    // worst rate first, then lowest group, the bits of positive floats order like them
+    let candidate = |group: usize, option: usize| {
+        let rate = problem.option_rate(group, option);
+        let lower = lower(group, option)?;
+        (rate > 0.0).then_some((rate.to_bits(), Reverse(group), option, lower))
+    };
+    let mut candidates: BinaryHeap<_> = genes.iter().enumerate()
+        .filter(|(_, option)| option.option() != 0)
+        .filter_map(|(group, option)| candidate(group, option.option()))
+        .collect();
+    while cost > capacity {
+        // the options left have no positive rate or no allowed option below them
+        let Some((_, Reverse(high_rate_gene), value, lower)) = candidates.pop() else {
+            break;
+        };
+        let (old_gain, old_cost) = problem.option_summary(high_rate_gene, value);
+        let (new_gain, new_cost) = problem.option_summary(high_rate_gene, lower);
+        gain_change += new_gain - old_gain;
+        cost_change += new_cost - old_cost;
+        cost += new_cost - old_cost;
+        genes[high_rate_gene] = G::from_option(lower);
+        if lower != 0 {
+            candidates.extend(candidate(high_rate_gene, lower));
+        }
+    }
+    (gain_change, cost_change)
+}
+
+/// Gain and cost of the options of `first` minus the ones of `second`, both starting at group
+/// `offset`.
+pub(crate) fn difference<G: Gene>(problem: &Problem, first: &[G], second: &[G], offset: usize) -> (i64, i64) {
+    let (mut gain, mut cost) = (0, 0);
+    for (i, (a, b)) in first.iter().zip(second).enumerate().filter(|(_, (a, b))| a != b) {
+        let (gain_a, cost_a) = problem.option_summary(offset + i, a.option());
+        let (gain_b, cost_b) = problem.option_summary(offset + i, b.option());
+        gain += gain_a - gain_b;
+        cost += cost_a - cost_b;
+    }
+    (gain, cost)
+}
+
+/// Sets a random group to a random option, the mutation of the genetic algorithm.
+pub(crate) fn change_option<G: Gene>(rng: &mut SmallRng, genes: &mut [G]) {
+    let index = rng.gen_range(0..genes.len());
+    let gene = rng.gen_range(0..4);
+    genes[index] = G::from_option(gene);
+}
+
+/// Same as [`change_option`], drawing the option with the preferences of `domain` and updating
+/// the totals of the chromosome.
+pub(crate) fn change_option_in(rng: &mut SmallRng, problem: &Problem, domain: &GeneDomain, chromosome: &mut Chromosome) {
+    let index = rng.gen_range(0..chromosome.genes.len());
+    let option = domain.random_option(rng, index);
+    chromosome.set_gene(problem, index, option as u8);
+}
+
+/// Exchanges the options of two random groups.
+pub(crate) fn swap_groups<G>(rng: &mut SmallRng, genes: &mut [G]) {
+    let first = rng.gen_range(0..genes.len());
+    let second = rng.gen_range(0..genes.len());
+    genes.swap(first, second);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    #[test]
+    fn test_repair_drops_worst_rate_first() {
+        let problem = make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+        ], 2);
+        let mut genes: Vec<usize> = vec![3, 3];
+        assert!(repair(&problem, &mut genes));
+        assert_eq!(genes, vec![1, 3]);
+        assert!(!repair(&problem, &mut genes));
+        assert_eq!(summary(&problem, &genes), (55, 20));
+    }
+
+    #[test]
+    fn test_repair_chromosome_and_difference_keep_totals() {
+        let problem = make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+        ], 2);
+        let mut chromosome = Chromosome::init_chromosome(vec![3, 3]);
+        chromosome.summarize(&problem);
+        assert!(repair_chromosome(&problem, &GeneDomain::full(2), &mut chromosome));
+        assert_eq!(chromosome.genes, vec![1, 3]);
+        assert_eq!((chromosome.gain, chromosome.cost), summary(&problem, &chromosome.genes));
+        assert_eq!(difference(&problem, &[3u8, 3], &[1, 0], 0), (65, 24));
+        assert_eq!(difference(&problem, &[3u8], &[1], 1), (30, 8));
+    }
+
+    #[test]
+    fn test_repair_stops_without_candidates() {
+        // the taken item has a negative gain, no option has a positive rate to step down
+        let problem = make_problem(5, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(-5, 10), make_item(20, 12), make_item(15, 22)],
+        ], 2);
+        let mut chromosome = Chromosome::init_chromosome(vec![0, 1]);
+        chromosome.summarize(&problem);
+        repair_chromosome(&problem, &GeneDomain::full(2), &mut chromosome);
+        assert_eq!(chromosome.genes, vec![0, 1]);
+        assert_eq!((chromosome.gain, chromosome.cost), (-5, 10));
+    }
+}
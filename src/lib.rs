@@ -0,0 +1,73 @@
+//! Genetic algorithm for the discounted {0-1} knapsack problem (D{0-1}KP).
+//!
+//! Items are organized in groups of three, where the third item combines the first two at a
+//! discounted cost, and at most one item per group can be taken. A solution is described by one
+//! choice per group: `0` when the group is left empty, `k` when its k-th item is taken.
+//!
+//! ```
+//! use discounted_zeroone_knapsack::{GeneticAlgorithm, Item, Problem, ProblemPreprocessor, SolverBuilder};
+//!
+//! let problem = Problem::new(20, vec![
+//!     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+//!     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+//! ]);
+//!
+//! let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+//! let mut executor = SolverBuilder::new()
+//!     .problem(problem)
+//!     .population_size(20)
+//!     .enhanced_seeding(true)
+//!     .preprocessing_result(preprocessing)
+//!     .build()
+//!     .unwrap();
+//! let solution = executor.run();
+//!
+//! assert_eq!(solution.choices().len(), 2);
+//! assert!(solution.cost() <= 20);
+//! ```
+
+pub mod annealing;
+pub mod bnb;
+pub mod brkga;
+pub mod builder;
+pub mod de;
+pub mod dp;
+pub mod error;
+pub mod fptas;
+pub mod generator;
+pub mod genetic;
+pub mod parser;
+pub mod portfolio;
+pub mod preprocessing;
+pub mod pso;
+pub mod reduction;
+pub mod report;
+pub mod solver;
+pub mod structure;
+pub mod tabu;
+mod utils;
+
+pub use crate::annealing::{AnnealingSchedule, AnnealingStatistics, Cooling, SimulatedAnnealing};
+pub use crate::bnb::{BranchAndBoundSolver, BranchAndBoundStatistics};
+pub use crate::brkga::{Brkga, BrkgaSettings};
+pub use crate::builder::SolverBuilder;
+pub use crate::de::{DeSettings, DifferentialEvolution};
+pub use crate::dp::DynamicProgrammingSolver;
+pub use crate::error::SolverError;
+pub use crate::fptas::{FptasSolver, FptasStatistics};
+pub use crate::genetic::{GeneticAlgorithm, KnapsackGeneticAlgorithm};
+pub use crate::parser::ProblemParser;
+pub use crate::portfolio::{Portfolio, PortfolioStatistics};
+pub use crate::preprocessing::{DominatedItem, FixedItem, PreprocessingReport, PreprocessingResult, ProblemPreprocessor};
+pub use crate::pso::{ParticleSwarm, PsoSettings};
+pub use crate::reduction::{ProblemReduction, ReducedSolver};
+pub use crate::solver::{Cancellation, Solver, SolverFactory, SolverRegistry, SolverStatistics};
+pub use crate::structure::chromosome::Chromosome;
+pub use crate::structure::configuration::{Configuration, ConfigurationByGenerations, PopulationSize};
+pub use crate::structure::constraint_handling::ConstraintHandling;
+pub use crate::structure::item::Item;
+pub use crate::structure::problem::Problem;
+pub use crate::structure::relaxation_result::DominanceRule;
+pub use crate::structure::seeding_mix::{SeedingCounts, SeedingMix, SeedingStrategy};
+pub use crate::structure::solution::Solution;
+pub use crate::tabu::{TabuSearch, TabuSettings, TabuStatistics};
@@ -1,26 +1,21 @@
-use crate::genetic::{GeneticAlgorithm, KnapsackGeneticAlgorithm};
-use crate::parser::*;
-use crate::preprocessing::{PreprocessingResult, ProblemPreprocessor};
-use crate::report::Report;
-use crate::structure::configuration::ConfigurationByGenerations;
-use crate::structure::problem::Problem;
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use discounted_zeroone_knapsack::report::{Csv, Report};
+use discounted_zeroone_knapsack::{
+    AnnealingSchedule, BrkgaSettings, Cancellation, ConstraintHandling, Cooling, DeSettings, PopulationSize, Problem, PreprocessingReport, ProblemParser, ProblemPreprocessor, PsoSettings, ReducedSolver, SeedingMix, SolverBuilder, SolverError,
+    SolverRegistry, TabuSettings,
+};
 use env_logger::Env;
-use log::{debug, info};
-use std::time::SystemTime;
+use log::{debug, error, info};
+use std::process;
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
-mod parser;
-mod structure;
-mod utils;
-mod genetic;
-mod report;
-mod generator;
-mod preprocessing;
-
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long)]
     file_path: String,
 
@@ -41,6 +36,118 @@ struct Args {
 
     #[arg(short, long, default_value = "false")]
     enhanced_enabled: bool,
+
+    #[arg(short, long, default_value = "5m")]
+    population_size: PopulationSize,
+
+    #[arg(long, default_value = "best=0.01,f0=0.04,random=0.95")]
+    seeding_mix: SeedingMix,
+
+    #[arg(long, default_value_t = 5)]
+    grasp_rcl_size: usize,
+
+    #[arg(long)]
+    seeding_file: Option<String>,
+
+    #[arg(short, long, default_value = "ga")]
+    algorithm: String,
+
+    #[arg(long, default_value_t = 1024)]
+    dp_memory_budget: usize,
+
+    #[arg(short, long)]
+    time_limit: Option<f64>,
+
+    #[arg(long, default_value = "false")]
+    bnb_ga_incumbent: bool,
+
+    #[arg(long, default_value_t = 4)]
+    portfolio_size: usize,
+
+    #[arg(long, default_value_t = 0.0)]
+    gap_tolerance: f64,
+
+    // the individuals of a seeding file have one choice per group of the original problem
+    #[arg(long, default_value = "false", conflicts_with = "seeding_file")]
+    reduce: bool,
+
+    #[arg(long, default_value = "false")]
+    unconstrained_domain: bool,
+
+    #[arg(long, default_value = "repair")]
+    constraint_handling: ConstraintHandling,
+
+    #[arg(long, default_value_t = 0.1)]
+    epsilon: f64,
+
+    #[arg(long, default_value = "geometric:0.95")]
+    sa_cooling: Cooling,
+
+    #[arg(long)]
+    sa_initial_temperature: Option<f64>,
+
+    #[arg(long, default_value_t = 0.8)]
+    sa_initial_acceptance: f64,
+
+    #[arg(long, default_value_t = 0)]
+    sa_reheat_interval: u32,
+
+    #[arg(long, default_value_t = 0.5)]
+    sa_reheat_ratio: f64,
+
+    #[arg(long, default_value_t = 10)]
+    tabu_tenure: usize,
+
+    #[arg(long, default_value_t = 100)]
+    tabu_candidates: usize,
+
+    #[arg(long, default_value_t = 0.5)]
+    tabu_diversification: f64,
+
+    #[arg(long, default_value_t = 0.7)]
+    pso_inertia: f64,
+
+    #[arg(long, default_value_t = 1.5)]
+    pso_cognitive: f64,
+
+    #[arg(long, default_value_t = 1.5)]
+    pso_social: f64,
+
+    #[arg(long, default_value_t = 4.0)]
+    pso_max_velocity: f64,
+
+    #[arg(long, default_value_t = 1.0)]
+    de_weight: f64,
+
+    #[arg(long, default_value_t = 0.3)]
+    de_crossover: f64,
+
+    #[arg(long, default_value_t = 0.2)]
+    brkga_elite: f64,
+
+    #[arg(long, default_value_t = 0.15)]
+    brkga_mutants: f64,
+
+    #[arg(long, default_value_t = 0.7)]
+    brkga_inheritance: f64,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the preprocessing of the problem instead of solving it
+    Preprocess {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+        /// Check the fixings against the dynamic programming, instances of up to 20 groups only
+        #[arg(long, default_value_t = false)]
+        verify: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    Table,
+    Json,
 }
 
 fn parse_args() -> Args {
@@ -51,17 +158,58 @@ fn initialize_problem(file_path: &str) -> Problem {
     <Problem as ProblemParser>::parse_input(file_path.to_string())
 }
 
-fn initialize_preprocessor(problem: &Problem) -> ProblemPreprocessor {
-    ProblemPreprocessor::new(problem)
+fn initialize_builder(args: &Args) -> SolverBuilder {
+    let mut builder = SolverBuilder::new()
+        .seed(args.seed)
+        .population_size(args.population_size)
+        .mutation_factor(args.initial_mutation_factor)
+        .stagnation_limit(args.no_upgrade_limit)
+        .enhanced_seeding(args.enhanced_enabled)
+        .seeding_mix(args.seeding_mix.clone())
+        .grasp_rcl_size(args.grasp_rcl_size)
+        .memory_budget(args.dp_memory_budget.saturating_mul(1024 * 1024))
+        .epsilon(args.epsilon)
+        .ga_incumbent(args.bnb_ga_incumbent)
+        .portfolio_size(args.portfolio_size)
+        .gap_tolerance(args.gap_tolerance)
+        .domain_constraints(!args.unconstrained_domain)
+        .constraint_handling(args.constraint_handling);
+    if let Some(seeding_file) = &args.seeding_file {
+        builder = builder.seeding_file(seeding_file.clone());
+    }
+    let mut schedule = AnnealingSchedule::new()
+        .cooling(args.sa_cooling)
+        .initial_acceptance(args.sa_initial_acceptance)
+        .reheating(args.sa_reheat_interval, args.sa_reheat_ratio);
+    if let Some(temperature) = args.sa_initial_temperature {
+        schedule = schedule.initial_temperature(temperature);
+    }
+    let tabu_settings = TabuSettings::new()
+        .tenure(args.tabu_tenure)
+        .candidates(args.tabu_candidates)
+        .diversification(args.tabu_diversification);
+    let pso_settings = PsoSettings::new()
+        .inertia(args.pso_inertia)
+        .cognitive(args.pso_cognitive)
+        .social(args.pso_social)
+        .max_velocity(args.pso_max_velocity);
+    let de_settings = DeSettings::new().differential_weight(args.de_weight).crossover_rate(args.de_crossover);
+    let brkga_settings = BrkgaSettings::new()
+        .elite_fraction(args.brkga_elite)
+        .mutant_fraction(args.brkga_mutants)
+        .inheritance(args.brkga_inheritance);
+    builder
+        .annealing_schedule(schedule)
+        .tabu_settings(tabu_settings)
+        .pso_settings(pso_settings)
+        .de_settings(de_settings)
+        .brkga_settings(brkga_settings)
 }
 
-fn initialize_configuration(args: &Args, seed: u64, population_size: u32, enhanced_enabled: bool) -> ConfigurationByGenerations {
-    ConfigurationByGenerations {
-        initial_mutation_factor: args.initial_mutation_factor,
-        no_upgrade_limit: args.no_upgrade_limit,
-        population_size,
-        seed,
-        enhanced_enabled,
+fn initialize_cancellation(args: &Args) -> Cancellation {
+    match args.time_limit {
+        Some(seconds) => Cancellation::new().time_limit(Duration::from_secs_f64(seconds)),
+        None => Cancellation::new(),
     }
 }
 
@@ -70,44 +218,55 @@ fn main() {
     env_logger::Builder::from_env(Env::default().default_filter_or(&args.log_level)).init();
     let problem = initialize_problem(&args.file_path);
 
-    let csv = report::CSV {
+    if let Some(Command::Preprocess { format, verify }) = args.command {
+        let mut preprocessor = ProblemPreprocessor::new(&problem);
+        let preprocessing = preprocessor.process_problem();
+        if verify {
+            if let Err(why) = preprocessor.verify_fixings(&preprocessing) {
+                error!("preprocessing fixings are not valid: {}", why);
+                process::exit(1);
+            }
+        }
+        let report = PreprocessingReport::new(&problem, &preprocessing);
+        match format {
+            OutputFormat::Table => print!("{}", report),
+            OutputFormat::Json => println!("{}", report.to_json()),
+        }
+        return;
+    }
+
+    let csv = Csv {
         path: if args.result_file_name.is_empty() { "metrics.csv".to_string() } else { args.result_file_name.clone() },
     };
 
     let start = SystemTime::now();
-    let mut preprocessing_result = PreprocessingResult::empty();
-    if args.enhanced_enabled {
-        let mut preprocessor = initialize_preprocessor(&problem);
-        preprocessing_result = preprocessor.process_problem();
+    let upper_bound = ProblemPreprocessor::new(&problem).dantzig_bound();
+    debug!("Dantzig upper bound: {}", upper_bound);
+    let registry = SolverRegistry::default();
+    let mut solver = registry.create(&args.algorithm, &initialize_builder(&args)).unwrap_or_else(|why| {
+        error!("{}, available solvers: {}", why, registry.names().join(", "));
+        process::exit(1);
+    });
+    if args.reduce {
+        solver = Box::new(ReducedSolver::new(solver));
     }
-
-    let configuration = initialize_configuration(
-        &args,
-        args.seed,
-        problem.size as u32 * 5,
-        args.enhanced_enabled,
-    );
-    let mut executor = <KnapsackGeneticAlgorithm as GeneticAlgorithm>::init(
-        problem.clone(),
-        Box::new(configuration),
-        &preprocessing_result,
-    );
-    let solution = executor.run();
+    let (solution, statistics) = solver.solve(problem, &initialize_cancellation(&args)).unwrap_or_else(|why| {
+        match why {
+            SolverError::MemoryBudgetExceeded { .. } => error!("{}, raise --dp-memory-budget or use another algorithm", why),
+            _ => error!("{}", why),
+        }
+        process::exit(1);
+    });
     let elapsed = start.elapsed().unwrap();
 
     debug!("Solution: {:?}", solution);
-    info!("Elapsed: {:.2?} best: {}", elapsed, solution.fitness);
-
-    Report::generate(
-        csv.clone(),
-        Uuid::new_v4().to_string(),
-        start,
-        args.file_path.clone(),
-        args.seed,
-        args.no_upgrade_limit,
-        problem.size as u32 * 5,
-        &solution,
-        elapsed,
-        args.enhanced_enabled,
-    );
-}
\ No newline at end of file
+    info!("Elapsed: {:.2?} best: {}", elapsed, solution.fitness());
+
+    Report::new(Uuid::new_v4().to_string(), args.file_path.clone(), start)
+        .seed(args.seed)
+        .algorithm(solver.name())
+        .upper_bound(upper_bound)
+        .statistics(&statistics)
+        .solution(&solution, elapsed)
+        .export(csv);
+}
@@ -6,8 +6,10 @@ use crate::structure::{make_item, make_problem};
 use crate::structure::item::Item;
 use crate::structure::problem::Problem;
 
-mod utils;
+pub(crate) mod utils;
 
+/// Reads a problem from an instance file: number of groups, capacity, then one line of gains
+/// per group and, after an empty line, one line of costs per group.
 pub trait ProblemParser {
     fn parse_input(file_name: String) -> Self;
 }
@@ -82,7 +84,7 @@ fn parse_headers(mut lines: io::Lines<io::BufReader<File>>) -> ((i32, u32), io::
 
 fn split_groups(mut lines: io::Lines<io::BufReader<File>>) -> (Vec<String>, io::Lines<io::BufReader<File>>) {
     let mut v: Vec<String> = Vec::new();
-    while let Some(line) = lines.next() {
+    for line in lines.by_ref() {
         let val = unwrap_next_line_result(line);
         if val.is_empty() {
             break;
@@ -0,0 +1,268 @@
+use crate::builder::SolverBuilder;
+use crate::error::SolverError;
+use crate::genetic::GeneticAlgorithm;
+use crate::preprocessing::ProblemPreprocessor;
+use crate::solver::Cancellation;
+use crate::structure::configuration::PopulationSize;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use log::{debug, info};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// best solution of the portfolio and the member that found it
+struct Incumbent {
+    genes: Vec<u8>,
+    fitness: i64,
+    member: usize,
+}
+
+/// Best solution shared between the members of a portfolio.
+#[derive(Clone, Default)]
+pub(crate) struct SharedIncumbent {
+    inner: Arc<Mutex<Option<Incumbent>>>,
+}
+
+impl SharedIncumbent {
+    /// Records the solution when it is strictly better than the incumbent.
+    pub(crate) fn offer(&self, genes: &[u8], fitness: i64, member: usize) {
+        let mut incumbent = self.inner.lock().unwrap();
+        if incumbent.as_ref().is_none_or(|incumbent| fitness > incumbent.fitness) {
+            debug!("Portfolio member {} improved the incumbent to {}", member, fitness);
+            *incumbent = Some(Incumbent { genes: genes.to_vec(), fitness, member });
+        }
+    }
+
+    /// The incumbent when it is strictly better than `fitness`, only then are its genes cloned.
+    pub(crate) fn better_than(&self, fitness: i64) -> Option<(Vec<u8>, i64)> {
+        self.inner.lock().unwrap().as_ref()
+            .filter(|incumbent| incumbent.fitness > fitness)
+            .map(|incumbent| (incumbent.genes.clone(), incumbent.fitness))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PortfolioStatistics {
+    pub(crate) labels: Vec<String>,
+    pub(crate) winner: usize,
+    pub(crate) runs: u32,
+}
+
+impl PortfolioStatistics {
+    /// Description of every member configuration.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Index of the member that found the best solution.
+    pub fn winner(&self) -> usize {
+        self.winner
+    }
+
+    pub fn winner_label(&self) -> &str {
+        &self.labels[self.winner]
+    }
+
+    /// Runs of the genetic algorithm over all members, counting restarts.
+    pub fn runs(&self) -> u32 {
+        self.runs
+    }
+}
+
+/// Several genetic algorithm configurations run on separate threads until a deadline.
+///
+/// Members publish their best chromosome to a shared incumbent after every generation and inject
+/// it into their population when another member found a better one. A member stopped by its
+/// stagnation limit restarts with another seed, so every member works until the cancellation
+/// deadline, which is required, or until the incumbent is within the gap tolerance of the bound.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{Cancellation, Item, Portfolio, Problem, SolverBuilder};
+/// use std::time::Duration;
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let cancellation = Cancellation::new().time_limit(Duration::from_millis(100));
+/// let (solution, statistics) = Portfolio::new(problem)
+///     .varied_members(&SolverBuilder::new().population_size(20), 2)
+///     .cancellation(cancellation)
+///     .solve()
+///     .unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// assert!(statistics.winner() < 2);
+/// ```
+pub struct Portfolio {
+    problem: Arc<Problem>,
+    members: Vec<(String, SolverBuilder)>,
+    cancellation: Cancellation,
+}
+
+fn scale(population_size: PopulationSize, factor: f64) -> PopulationSize {
+    match population_size {
+        PopulationSize::Absolute(size) => PopulationSize::Absolute(((size as f64 * factor).round() as u32).max(2)),
+        PopulationSize::PerGroup(multiple) => PopulationSize::PerGroup(multiple * factor),
+    }
+}
+
+impl Portfolio {
+    /// Portfolio over `problem`, shared by every member without being copied.
+    pub fn new(problem: impl Into<Arc<Problem>>) -> Self {
+        Portfolio {
+            problem: problem.into(),
+            members: Vec::new(),
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    /// Adds a genetic algorithm configuration, the problem set on `builder` is ignored.
+    pub fn member(mut self, label: impl Into<String>, builder: SolverBuilder) -> Self {
+        self.members.push((label.into(), builder));
+        self
+    }
+
+    /// Adds `count` variations of `base`, with consecutive seeds, enhanced seeding alternately
+    /// on and off, and the mutation factor and population size halved or doubled in turn.
+    pub fn varied_members(mut self, base: &SolverBuilder, count: usize) -> Self {
+        let configuration = &base.configuration;
+        for i in 0..count {
+            let factor = [1.0, 2.0, 0.5][(i / 2) % 3];
+            let seed = configuration.seed + i as u64;
+            let enhanced = configuration.enhanced_enabled ^ (i % 2 == 1);
+            let mutation_factor = ((configuration.initial_mutation_factor as f64 * factor).round() as u16).clamp(1, 1000);
+            let population_size = scale(base.population_size, [1.0, 2.0, 0.5][i % 3]);
+            let label = format!("seed={} enhanced={} mutation={} population={}", seed, enhanced, mutation_factor, population_size);
+            let builder = base.clone()
+                .seed(seed)
+                .enhanced_seeding(enhanced)
+                .mutation_factor(mutation_factor)
+                .population_size(population_size);
+            self = self.member(label, builder);
+        }
+        self
+    }
+
+    /// Deadline at which all members stop.
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    // runs one member until the deadline, returning the generations and runs done
+    fn run_member(&self, member: usize, builder: SolverBuilder, incumbent: &SharedIncumbent) -> Result<(u32, u32), SolverError> {
+        let seed = builder.configuration.seed;
+        let (mut generations, mut runs) = (0, 0);
+        while !self.cancellation.is_cancelled() {
+            // restarts take seeds no other member starts from
+            let restart_seed = seed.wrapping_add(runs as u64 * self.members.len() as u64);
+            let mut executor = builder.clone().seed(restart_seed).problem(Arc::clone(&self.problem)).build()?;
+            executor.set_cancellation(self.cancellation.clone());
+            executor.set_incumbent(incumbent.clone(), member);
+            let solution = executor.run();
+            let genes: Vec<u8> = solution.choices().iter().map(|choice| *choice as u8).collect();
+            incumbent.offer(&genes, solution.fitness(), member);
+            generations += solution.generations();
+            runs += 1;
+            // the incumbent is injected into every member, which all stop once it reaches the bound
+            if executor.bound_reached() {
+                break;
+            }
+        }
+        Ok((generations, runs))
+    }
+
+    pub fn solve(&self) -> Result<(Solution, PortfolioStatistics), SolverError> {
+        if self.members.is_empty() {
+            return Err(SolverError::EmptyPortfolio);
+        }
+        if self.cancellation.deadline().is_none() {
+            return Err(SolverError::MissingDeadline);
+        }
+        info!("Running portfolio of {} genetic algorithms for knapsack capacity: {}, selection size: {} ", self.members.len(), self.problem.capacity, self.problem.size);
+
+        // the preprocessing is shared by the members with enhanced seeding
+        let preprocessing_result = self.members.iter()
+            .any(|(_, builder)| builder.configuration.enhanced_enabled)
+            .then(|| ProblemPreprocessor::new(&self.problem).process_problem());
+
+        let incumbent = SharedIncumbent::default();
+        let results: Vec<Result<(u32, u32), SolverError>> = thread::scope(|scope| {
+            let handles: Vec<_> = self.members.iter().enumerate().map(|(member, (_, builder))| {
+                let mut builder = builder.clone();
+                if let Some(result) = &preprocessing_result {
+                    builder = builder.preprocessing_result(result.clone());
+                }
+                let incumbent = &incumbent;
+                scope.spawn(move || self.run_member(member, builder, incumbent))
+            }).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        let (mut generations, mut runs) = (0, 0);
+        for result in results {
+            let (member_generations, member_runs) = result?;
+            generations += member_generations;
+            runs += member_runs;
+        }
+
+        let Incumbent { genes, fitness, member } = incumbent.inner.lock().unwrap().take().unwrap_or(Incumbent {
+            genes: vec![0; self.problem.data.len()],
+            fitness: 0,
+            member: 0,
+        });
+        let genes: Vec<usize> = genes.iter().map(|gene| *gene as usize).collect();
+        let statistics = PortfolioStatistics {
+            labels: self.members.iter().map(|(label, _)| label.clone()).collect(),
+            winner: member,
+            runs,
+        };
+        info!("Portfolio best: {} found by member {} ({})", fitness, member, statistics.winner_label());
+        Ok((Solution::from_choices(&self.problem, genes, generations), statistics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::make_thirty_groups;
+    use std::time::Duration;
+
+    #[test]
+    fn test_varied_members() {
+        let base = SolverBuilder::new().seed(10).mutation_factor(10).population_size(PopulationSize::PerGroup(4.0));
+        let portfolio = Portfolio::new(make_thirty_groups()).varied_members(&base, 4);
+        let labels: Vec<_> = portfolio.members.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, vec![
+            "seed=10 enhanced=false mutation=10 population=4m",
+            "seed=11 enhanced=true mutation=10 population=8m",
+            "seed=12 enhanced=false mutation=20 population=2m",
+            "seed=13 enhanced=true mutation=20 population=4m",
+        ]);
+    }
+
+    #[test]
+    fn test_scaled_population_keeps_two_parents() {
+        assert_eq!(scale(PopulationSize::Absolute(2), 0.5), PopulationSize::Absolute(2));
+        assert_eq!(scale(PopulationSize::Absolute(40), 0.5), PopulationSize::Absolute(20));
+    }
+
+    #[test]
+    fn test_solve_shares_incumbent_until_deadline() {
+        let cancellation = Cancellation::new().time_limit(Duration::from_millis(300));
+        let (solution, statistics) = Portfolio::new(make_thirty_groups())
+            .varied_members(&SolverBuilder::new().population_size(30).stagnation_limit(5), 3)
+            .cancellation(cancellation)
+            .solve()
+            .unwrap();
+
+        assert!(solution.cost() <= 300);
+        assert!(statistics.winner() < 3);
+        assert!(statistics.runs() > 3);
+    }
+
+    #[test]
+    fn test_solve_requires_deadline() {
+        let result = Portfolio::new(make_thirty_groups()).varied_members(&SolverBuilder::new(), 2).solve();
+        assert_eq!(result.unwrap_err(), SolverError::MissingDeadline);
+    }
+}
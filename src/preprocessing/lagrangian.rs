@@ -0,0 +1,187 @@
+use crate::preprocessing::ratio::Ratio;
+use crate::preprocessing::ProblemPreprocessor;
+use crate::structure::lagrangian_result::LagrangianResult;
+use log::debug;
+
+const MAX_ITERATIONS: u32 = 300;
+// the step size is halved after this many iterations without lowering the bound
+const HALVING_PATIENCE: u32 = 5;
+const MIN_STEP_SIZE: f64 = 1e-4;
+
+/// Solution of the Lagrangian subproblem for a multiplier.
+struct Relaxed {
+    value: f64,
+    choices: Vec<usize>,
+    cost: i64,
+}
+
+impl ProblemPreprocessor<'_> {
+    /// Lagrangian relaxation of the capacity constraint, solved by subgradient optimization.
+    ///
+    /// For a multiplier `l` every group takes independently the item maximizing `gain - l * cost`,
+    /// or nothing, and `l * capacity` plus these profits bounds the optimum. Every relaxed
+    /// solution is turned into a feasible one by the Lagrangian heuristic, the best one is kept.
+    ///
+    /// ```
+    /// use discounted_zeroone_knapsack::{Item, Problem, ProblemPreprocessor};
+    ///
+    /// let problem = Problem::new(20, vec![
+    ///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+    ///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+    /// ]);
+    /// let result = ProblemPreprocessor::new(&problem).lagrangian_relaxation();
+    /// assert!(result.bound() >= 55.0);
+    /// assert_eq!(result.value(), 55);
+    /// ```
+    pub fn lagrangian_relaxation(&self) -> LagrangianResult {
+        let capacity = self.problem.capacity as i64;
+        let (gain, cost) = self.problem.data.iter().flatten()
+            .filter(|item| item.cost > 0 && item.cost <= capacity && item.gain > 0)
+            .fold((0, 0), |(gain, cost), item| (gain + item.gain, cost + item.cost));
+        let mut multiplier = if cost > 0 { gain as f64 / cost as f64 } else { 0.0 };
+
+        let mut bound = f64::INFINITY;
+        let mut best_multiplier = multiplier;
+        let mut best_choices = vec![0; self.problem.data.len()];
+        let mut best_value = 0;
+        let mut step_size = 2.0;
+        let mut without_improvement = 0;
+        let mut iterations = 0;
+        while iterations < MAX_ITERATIONS && step_size >= MIN_STEP_SIZE {
+            iterations += 1;
+            let relaxed = self.lagrangian_subproblem(multiplier);
+            if relaxed.value < bound {
+                bound = relaxed.value;
+                best_multiplier = multiplier;
+                without_improvement = 0;
+            } else {
+                without_improvement += 1;
+                if without_improvement >= HALVING_PATIENCE {
+                    step_size /= 2.0;
+                    without_improvement = 0;
+                }
+            }
+
+            let subgradient = (capacity - relaxed.cost) as f64;
+            let (choices, value) = self.lagrangian_repair(multiplier, relaxed.choices);
+            if value > best_value {
+                best_value = value;
+                best_choices = choices;
+            }
+            // gains are integers, a solution reaching the floor of the bound is optimal
+            if bound.floor() <= best_value as f64 || subgradient == 0.0 {
+                break;
+            }
+            // Polyak step towards the best known solution
+            let step = step_size * (relaxed.value - best_value as f64) / (subgradient * subgradient);
+            multiplier = (multiplier - step * subgradient).max(0.0);
+        }
+        debug!("Lagrangian relaxation: bound {} with multiplier {} after {} iterations, heuristic value {}", bound, best_multiplier, iterations, best_value);
+        LagrangianResult::new(bound.max(best_value as f64), best_multiplier, best_choices, best_value, iterations)
+    }
+
+    // items that do not fit alone are left out, they are in no feasible solution
+    fn lagrangian_subproblem(&self, multiplier: f64) -> Relaxed {
+        let capacity = self.problem.capacity as i64;
+        let mut value = multiplier * capacity as f64;
+        let mut choices = vec![0; self.problem.data.len()];
+        let mut cost = 0;
+        for (choice, group) in choices.iter_mut().zip(&self.problem.data) {
+            let mut best_profit = 0.0;
+            for (k, item) in group.iter().enumerate().filter(|(_, item)| item.cost <= capacity) {
+                let profit = item.gain as f64 - multiplier * item.cost as f64;
+                if profit > best_profit {
+                    best_profit = profit;
+                    *choice = k + 1;
+                }
+            }
+            if *choice != 0 {
+                value += best_profit;
+                cost += group[*choice - 1].cost;
+            }
+        }
+        Relaxed { value, choices, cost }
+    }
+
+    /// Lagrangian heuristic: empties the groups with the lowest Lagrangian profit per cost until
+    /// the relaxed solution fits, then upgrades groups by decreasing efficiency while the
+    /// capacity allows it.
+    fn lagrangian_repair(&self, multiplier: f64, mut choices: Vec<usize>) -> (Vec<usize>, i64) {
+        let data = &self.problem.data;
+        let item = |group: usize, choice: usize| (choice != 0).then(|| &data[group][choice - 1]);
+        let gain_of = |group: usize, choice: usize| item(group, choice).map_or(0, |item| item.gain);
+        let cost_of = |group: usize, choice: usize| item(group, choice).map_or(0, |item| item.cost);
+
+        let mut cost: i64 = choices.iter().enumerate().map(|(i, choice)| cost_of(i, *choice)).sum();
+        let mut remaining_capacity = self.problem.capacity as i64 - cost;
+        if remaining_capacity < 0 {
+            let mut taken: Vec<usize> = (0..choices.len()).filter(|i| choices[*i] != 0).collect();
+            let profit_per_cost = |i: usize| {
+                let (gain, cost) = (gain_of(i, choices[i]), cost_of(i, choices[i]));
+                (gain as f64 - multiplier * cost as f64) / cost.max(1) as f64
+            };
+            taken.sort_by(|a, b| profit_per_cost(*a).total_cmp(&profit_per_cost(*b)));
+            for i in taken {
+                if remaining_capacity >= 0 {
+                    break;
+                }
+                remaining_capacity += cost_of(i, choices[i]);
+                cost -= cost_of(i, choices[i]);
+                choices[i] = 0;
+            }
+        }
+
+        // every upgrade from the repaired solution, checked again against the current choice
+        let mut upgrades: Vec<(usize, usize)> = vec![];
+        for (i, group) in data.iter().enumerate() {
+            upgrades.extend((1..=group.len()).filter(|k| gain_of(i, *k) > gain_of(i, choices[i])).map(|k| (i, k)));
+        }
+        // an upgrade saving capacity ranks first
+        let efficiency = |(i, k): (usize, usize)| Ratio::new(gain_of(i, k) - gain_of(i, choices[i]), (cost_of(i, k) - cost_of(i, choices[i])).max(0));
+        upgrades.sort_by_key(|upgrade| std::cmp::Reverse(efficiency(*upgrade)));
+        for (i, k) in upgrades {
+            let extra_cost = cost_of(i, k) - cost_of(i, choices[i]);
+            if gain_of(i, k) > gain_of(i, choices[i]) && extra_cost <= remaining_capacity {
+                remaining_capacity -= extra_cost;
+                cost += extra_cost;
+                choices[i] = k;
+            }
+        }
+        debug_assert!(cost <= self.problem.capacity as i64);
+
+        let value = choices.iter().enumerate().map(|(i, choice)| gain_of(i, *choice)).sum();
+        (choices, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dp::DynamicProgrammingSolver;
+    use crate::structure::{make_item, make_problem};
+    use crate::utils;
+    use rand::Rng;
+
+    #[test]
+    fn test_lagrangian_bound_and_heuristic_surround_the_optimum() {
+        let mut rng = utils::make_rng(5);
+        for _ in 0..30 {
+            let m: usize = rng.gen_range(1..15);
+            let data: Vec<_> = (0..m).map(|_| {
+                (0..3).map(|_| make_item(rng.gen_range(1..60), rng.gen_range(1..40))).collect()
+            }).collect();
+            let total: i64 = data.iter().map(|g: &Vec<_>| g[2].cost).sum();
+            let problem = make_problem(rng.gen_range(0..total as u32 + 1), data, m as i32);
+            let optimum = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap().fitness;
+
+            let result = ProblemPreprocessor::new(&problem).lagrangian_relaxation();
+            assert!(result.bound() >= optimum as f64 - 1e-6);
+            assert!(result.value() <= optimum);
+            let cost: i64 = result.choices().iter().enumerate()
+                .filter(|(_, choice)| **choice != 0)
+                .map(|(i, choice)| problem.data[i][choice - 1].cost)
+                .sum();
+            assert!(cost <= problem.capacity as i64);
+        }
+    }
+}
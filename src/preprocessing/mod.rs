@@ -1,16 +1,33 @@
 use crate::structure::fix_result::UBFixResult;
 use crate::structure::item::Item;
 use crate::structure::item_preprocessing::ItemPreprocessing;
+use crate::structure::lagrangian_result::LagrangianResult;
 use crate::structure::problem::Problem;
-use crate::structure::relaxation_result::LPRelaxationResult;
+use crate::structure::reduced_cost_result::ReducedCostResult;
+use crate::structure::relaxation_result::{DominanceRule, LPRelaxationResult};
+use ratio::{greedy_order, ratio_of, Ratio};
+
+pub use report::{DominatedItem, FixedItem, PreprocessingReport};
+
+mod lagrangian;
+mod ratio;
+mod reduced_cost;
+mod report;
+mod ub_fix;
+
+// (group, item) removed by the LP dominance and the rule removing it
+type Dominated = ((usize, usize), DominanceRule);
 
 pub struct ProblemPreprocessor<'a> {
     problem: &'a Problem,
 }
 
+#[derive(Debug, Clone)]
 pub struct PreprocessingResult {
     pub relaxation_result: LPRelaxationResult,
     pub ub_fix_result: UBFixResult,
+    pub lagrangian_result: LagrangianResult,
+    pub reduced_cost_result: ReducedCostResult,
 }
 
 impl PreprocessingResult {
@@ -18,6 +35,8 @@ impl PreprocessingResult {
         PreprocessingResult {
             relaxation_result,
             ub_fix_result,
+            lagrangian_result: LagrangianResult::empty(),
+            reduced_cost_result: ReducedCostResult::empty(),
         }
     }
 
@@ -25,6 +44,8 @@ impl PreprocessingResult {
         PreprocessingResult {
             relaxation_result: LPRelaxationResult::empty(),
             ub_fix_result: UBFixResult::empty(),
+            lagrangian_result: LagrangianResult::empty(),
+            reduced_cost_result: ReducedCostResult::empty(),
         }
     }
 }
@@ -36,50 +57,56 @@ impl<'a> ProblemPreprocessor<'a> {
         }
     }
 
+    /// Whether the increment from `to_check` to `dominant` is at least as efficient as
+    /// `to_check`, compared exactly. An item identical to `dominant` is dominated by it.
     fn is_dominant(dominant: &Item, to_check: &Item) -> bool {
-        let gain_difference = dominant.gain as f64 - to_check.gain as f64;
-        let cost_difference = dominant.cost as f64 - to_check.cost as f64;
-        (gain_difference / cost_difference) >= to_check.gain as f64 / to_check.cost as f64
+        ProblemPreprocessor::is_identical(dominant, to_check)
+            || Ratio::between(dominant, to_check) >= Ratio::of(to_check)
     }
 
+    /// Whether `to_check` lies under the segment from `down` to `up`, compared exactly. An item
+    /// identical to one of the two ends is dominated.
     fn is_ranged_dominated(up: &Item, down: &Item, to_check: &Item) -> bool {
-        let up_gain_difference = up.gain as f64 - to_check.gain as f64;
-        let up_cost_difference = up.cost as f64 - to_check.cost as f64;
-
-        let down_gain_difference = to_check.gain as f64 - down.gain as f64;
-        let down_cost_difference = to_check.cost as f64 - down.cost as f64;
-        (up_gain_difference / up_cost_difference) >= (down_gain_difference / down_cost_difference)
+        ProblemPreprocessor::is_identical(up, to_check)
+            || ProblemPreprocessor::is_identical(to_check, down)
+            || Ratio::between(up, to_check) >= Ratio::between(to_check, down)
     }
 
+    fn is_identical(a: &Item, b: &Item) -> bool {
+        a.gain == b.gain && a.cost == b.cost
+    }
 
     // equation 13 or equation 14
-    fn is_first_lp_dominated(&self, vec: Vec<Item>) -> bool {
+    fn first_lp_dominance(&self, vec: Vec<Item>) -> Option<DominanceRule> {
         let first = &vec[0];
         let second = &vec[1];
         let third = &vec[2];
 
-        // equation 13
-        let is_second_dominated = ProblemPreprocessor::is_dominant(second, first);
-        // equation 14
-        let is_third_dominated = ProblemPreprocessor::is_dominant(third, first);
-
-        is_second_dominated || is_third_dominated
+        if ProblemPreprocessor::is_dominant(second, first) {
+            Some(DominanceRule::Eq13)
+        } else if ProblemPreprocessor::is_dominant(third, first) {
+            Some(DominanceRule::Eq14)
+        } else {
+            None
+        }
     }
 
-    // equation 15 or equation 16
-    fn is_second_lp_dominated(&self, vec: Vec<Item>) -> bool {
+    // equation 16 or equation 15
+    fn second_lp_dominance(&self, vec: Vec<Item>) -> Option<DominanceRule> {
         let first = &vec[0];
         let second = &vec[1];
         let third = &vec[2];
 
-        let is_first_dominated = ProblemPreprocessor::is_ranged_dominated(third, first, second);
-
-        let is_third_dominated = ProblemPreprocessor::is_dominant(third, second);
-
-        is_third_dominated || is_first_dominated
+        if ProblemPreprocessor::is_dominant(third, second) {
+            Some(DominanceRule::Eq16)
+        } else if ProblemPreprocessor::is_ranged_dominated(third, first, second) {
+            Some(DominanceRule::Eq15)
+        } else {
+            None
+        }
     }
 
-    fn lp_relaxation_eliminate_by_dominance(&self) -> (Vec<(usize, usize)>, Vec<[ItemPreprocessing; 3]>) {
+    fn lp_relaxation_eliminate_by_dominance(&self) -> (Vec<Dominated>, Vec<[ItemPreprocessing; 3]>) {
         let data = self.problem.data.clone();
         let mut f_0 = vec![];
 
@@ -91,29 +118,29 @@ impl<'a> ProblemPreprocessor<'a> {
             ];
 
             // Check if item (i, 1) is LP-dominated
-            if self.is_first_lp_dominated(current_set.clone()) {
+            if let Some(rule) = self.first_lp_dominance(current_set.clone()) {
                 itemp[0].ratio = f64::MIN;
-                f_0.push((index, 0));
+                f_0.push(((index, 0), rule));
             } else {
                 itemp[0].gain = current_set[0].gain;
                 itemp[0].cost = current_set[0].cost;
-                itemp[0].ratio = itemp[0].gain as f64 / itemp[0].cost as f64;
+                itemp[0].ratio = ratio_of(itemp[0].gain, itemp[0].cost);
             }
 
             // Check if item (i, 2) is LP-dominated
-            if self.is_second_lp_dominated(current_set.clone()) {
+            if let Some(rule) = self.second_lp_dominance(current_set.clone()) {
                 itemp[1].ratio = f64::MIN;
-                f_0.push((index, 1));
+                f_0.push(((index, 1), rule));
                 itemp[2].gain = current_set[2].gain - itemp[0].gain;
                 itemp[2].cost = current_set[2].cost - itemp[0].cost;
-                itemp[2].ratio = itemp[2].gain as f64 / itemp[2].cost as f64;
+                itemp[2].ratio = ratio_of(itemp[2].gain, itemp[2].cost);
             } else {
                 itemp[1].gain = current_set[1].gain - itemp[0].gain;
                 itemp[1].cost = current_set[1].cost - itemp[0].cost;
-                itemp[1].ratio = itemp[1].gain as f64 / itemp[1].cost as f64;
-                itemp[2].gain = current_set[2].gain - itemp[1].gain;
-                itemp[2].cost = current_set[2].cost - itemp[1].cost;
-                itemp[2].ratio = itemp[2].gain as f64 / itemp[2].cost as f64;
+                itemp[1].ratio = ratio_of(itemp[1].gain, itemp[1].cost);
+                itemp[2].gain = current_set[2].gain - current_set[1].gain;
+                itemp[2].cost = current_set[2].cost - current_set[1].cost;
+                itemp[2].ratio = ratio_of(itemp[2].gain, itemp[2].cost);
             }
 
             itemp
@@ -124,6 +151,7 @@ impl<'a> ProblemPreprocessor<'a> {
     fn kp_greedy(&self, relaxed_original: Vec<[ItemPreprocessing; 3]>, f_0: Vec<(usize, usize)>) -> LPRelaxationResult {
         let m = self.problem.size;
         let mut remaining_capacity = self.problem.capacity as i64;
+        let mut low_capacity = 0;
         let mut x = vec![[0.0; 3]; m as usize];
         let mut x_up = vec![[0.0; 3]; m as usize];
         let mut j: usize = 0;
@@ -132,9 +160,10 @@ impl<'a> ProblemPreprocessor<'a> {
 
         // order by e
         let mut relaxed: Vec<&ItemPreprocessing> = relaxed_original.iter().flat_map(|inner_vec| inner_vec.iter()).collect();
-        relaxed.sort_by(|a, b| b.ratio.partial_cmp(&a.ratio).unwrap());
+        relaxed.sort_by(|a, b| greedy_order(a, b));
 
-        while remaining_capacity > 0 && j < relaxed.len() {
+        // dominated and removed items are sorted last with ratio f64::MIN, they never improve the bound
+        while remaining_capacity > 0 && j < relaxed.len() && relaxed[j].ratio > 0.0 {
             let i = relaxed[j].set_index;
             let k = relaxed[j].inner_index;
             if remaining_capacity > relaxed[j].cost {
@@ -145,88 +174,98 @@ impl<'a> ProblemPreprocessor<'a> {
                 x[i][k] = 1.0;
                 x_up[i][k] = 1.0;
             } else {
+                // x keeps the previous item of the group, v_low is its value
                 v_low = v_up;
+                low_capacity = remaining_capacity;
                 x_up[i][k] = remaining_capacity as f64 / relaxed[j].cost as f64;
                 v_up += relaxed[j].gain as f64 * x_up[i][k];
                 remaining_capacity = 0;
                 for inner_k in 0..3 {
                     if inner_k != k && x_up[i][inner_k] == 1.0 {
                         x_up[i][inner_k] = 1.0 - x_up[i][k];
-                        x[i][inner_k] = 0.0;
                     }
                 }
             }
             j += 1;
         }
 
-        j = 0;
+        if remaining_capacity > 0 {
+            v_low = v_up;
+            low_capacity = remaining_capacity;
+        }
 
-        while j < ((3 * m) - (f_0.len() as i32)) as usize {
-            let i = relaxed[j].set_index;
-            let k = relaxed[j].inner_index;
-            if remaining_capacity > relaxed[j].cost && ProblemPreprocessor::all_zero(&x[i]) {
+        // fill the capacity left by the break item with whole items of the empty groups
+        for item in relaxed.iter().filter(|item| item.ratio != f64::MIN) {
+            let i = item.set_index;
+            let k = item.inner_index;
+            let original = &self.problem.data[i][k];
+            if low_capacity >= original.cost && ProblemPreprocessor::all_zero(&x[i]) {
                 x[i][k] = 1.0;
-                v_low += relaxed[j].gain as f64;
-                remaining_capacity -= relaxed[j].cost;
+                v_low += original.gain as f64;
+                low_capacity -= original.cost;
             }
-            j += 1;
         }
 
-        LPRelaxationResult::new(f_0, x, x_up, v_up, v_low, relaxed_original)
+        LPRelaxationResult::new(f_0, x_up, x, v_up, v_low, relaxed_original)
     }
 
-    fn ub_fix(&self, lp_relaxation_result: LPRelaxationResult) -> UBFixResult {
-        let data = self.problem.data.clone();
-        let x_up = lp_relaxation_result.x_up;
-        let f_0: Vec<(usize, usize)> = lp_relaxation_result.f_0;
-        let mut f_1: Vec<(usize, usize)> = vec![];
-        let mut v_low_best = 2.0 * lp_relaxation_result.v_low;
-        let mut x_best = lp_relaxation_result.x.clone();
-        for index in 0..data.len() {
-            let mut temp_relaxed = lp_relaxation_result.relaxed.clone();
-            let current_set = data[index].clone();
-            if x_up[index][2] == 1.0 {
-                if f_0.contains(&(index, 1 as usize)) {
-                    let first = &current_set[0];
-                    let second = &current_set[1];
-
-                    // equation 13
-                    let is_dominated_by_second = ProblemPreprocessor::is_dominant(second, first);
-                    if is_dominated_by_second {
-                        temp_relaxed[index][0].gain = 0;
-                        temp_relaxed[index][0].cost = 0;
-                        temp_relaxed[index][0].ratio = f64::MIN;
-                    } else {
-                        temp_relaxed[index][0].gain = current_set[0].gain;
-                        temp_relaxed[index][0].cost = current_set[0].cost;
-                        temp_relaxed[index][0].ratio = temp_relaxed[index][0].gain as f64 / temp_relaxed[index][0].cost as f64;
-                    }
-                } else {
-                    temp_relaxed[index][0].gain = current_set[0].gain;
-                    temp_relaxed[index][0].cost = current_set[0].cost;
-                    temp_relaxed[index][0].ratio = temp_relaxed[index][0].gain as f64 / temp_relaxed[index][0].cost as f64;
-                    temp_relaxed[index][1].gain = current_set[1].gain - temp_relaxed[index][0].gain;
-                    temp_relaxed[index][1].cost = current_set[1].cost - temp_relaxed[index][0].cost;
-                    temp_relaxed[index][1].ratio = temp_relaxed[index][1].gain as f64 / temp_relaxed[index][1].cost as f64;
-                }
-                temp_relaxed[index][2].gain = 0;
-                temp_relaxed[index][2].cost = 0;
-                temp_relaxed[index][2].ratio = f64::MIN;
+    /// Dantzig bound of the LP relaxation, computed on the upper convex hull of every group.
+    ///
+    /// Unlike the relaxation used by the UB-fix it does not depend on the order of the items in
+    /// a group and it is always a valid upper bound.
+    pub fn dantzig_bound(&self) -> f64 {
+        self.dantzig_greedy().0
+    }
 
-                let res_i = self.kp_greedy(temp_relaxed, f_0.clone());
-                if res_i.v_low >= v_low_best {
-                    v_low_best = res_i.v_low;
-                    x_best = res_i.x;
+    // Dantzig bound with the choices of the increments taken whole, the bound exceeds their gain
+    // by less than the increment left fractional, so by less than the item this increment reaches
+    pub(crate) fn dantzig_greedy(&self) -> (f64, Vec<usize>) {
+        let capacity = self.problem.capacity as i64;
+        // (group, item, cost, gain) of every increment
+        let mut increments: Vec<(usize, usize, i64, i64)> = vec![];
+        for (i, group) in self.problem.data.iter().enumerate() {
+            let mut items: Vec<(usize, i64, i64)> = group.iter().enumerate()
+                .filter(|(_, item)| item.cost <= capacity && item.gain > 0)
+                .map(|(k, item)| (k, item.cost.max(0), item.gain))
+                .collect();
+            items.sort_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)));
+
+            let mut hull: Vec<(usize, i64, i64)> = vec![(usize::MAX, 0, 0)];
+            for (k, cost, gain) in items {
+                if gain <= hull.last().unwrap().2 {
+                    continue;
                 }
-                if res_i.v_up.ceil() <= v_low_best {
-                    f_1.push((index, 1));
-                } else {
-                    print!("{} ", index);
+                // drop the points left below the segment reaching the new item
+                while hull.len() >= 2 {
+                    let (_, c1, g1) = hull[hull.len() - 2];
+                    let (_, c2, g2) = hull[hull.len() - 1];
+                    if (g2 - g1) as i128 * (cost - c1) as i128 <= (gain - g1) as i128 * (c2 - c1) as i128 {
+                        hull.pop();
+                    } else {
+                        break;
+                    }
                 }
+                hull.push((k, cost, gain));
             }
+            increments.extend(hull.windows(2).map(|w| (i, w[1].0, w[1].1 - w[0].1, w[1].2 - w[0].2)));
         }
-
-        UBFixResult::new(f_1, x_best, v_low_best)
+        // stable, and the ratios decrease along a hull, so the increments of a group keep their order
+        increments.sort_by(|a, b| (b.3 as i128 * a.2 as i128).cmp(&(a.3 as i128 * b.2 as i128)));
+
+        let mut remaining_capacity = capacity;
+        let mut bound = 0.0;
+        let mut choices = vec![0; self.problem.data.len()];
+        for (group, item, cost, gain) in increments {
+            if cost <= remaining_capacity {
+                remaining_capacity -= cost;
+                bound += gain as f64;
+                choices[group] = item + 1;
+            } else {
+                bound += gain as f64 * remaining_capacity as f64 / cost as f64;
+                break;
+            }
+        }
+        (bound, choices)
     }
 
     fn all_zero(x: &[f64; 3]) -> bool {
@@ -235,15 +274,34 @@ impl<'a> ProblemPreprocessor<'a> {
 
     fn lp_relaxation(&self) -> LPRelaxationResult {
         // step 1
-        let (f_0, relaxed) = self.lp_relaxation_eliminate_by_dominance();
+        let (dominated, relaxed) = self.lp_relaxation_eliminate_by_dominance();
+        let (f_0, rules) = dominated.into_iter().unzip();
         // step 2 and 3
-        self.kp_greedy(relaxed, f_0)
+        let mut result = self.kp_greedy(relaxed, f_0);
+        result.rules = rules;
+        result
     }
 
-    pub(crate) fn process_problem(&mut self) -> PreprocessingResult {
+    /// Runs the LP relaxation, the UB-fix, the Lagrangian relaxation and the reduced-cost fixing
+    /// on the problem.
+    ///
+    /// ```
+    /// use discounted_zeroone_knapsack::{Item, Problem, ProblemPreprocessor};
+    ///
+    /// let problem = Problem::new(20, vec![
+    ///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+    ///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+    /// ]);
+    /// let result = ProblemPreprocessor::new(&problem).process_problem();
+    /// assert!(result.relaxation_result.v_up() >= result.relaxation_result.v_low());
+    /// ```
+    pub fn process_problem(&mut self) -> PreprocessingResult {
         let relaxation_result = self.lp_relaxation();
         let ub_fix_result = self.ub_fix(relaxation_result.clone());
-        PreprocessingResult::new(relaxation_result, ub_fix_result)
+        let mut result = PreprocessingResult::new(relaxation_result, ub_fix_result);
+        result.lagrangian_result = self.lagrangian_relaxation();
+        result.reduced_cost_result = self.reduced_cost_fixing(result.lagrangian_result.multiplier, result.ub_fix_result.v_best);
+        result
     }
 }
 
@@ -252,7 +310,58 @@ impl<'a> ProblemPreprocessor<'a> {
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use crate::dp::DynamicProgrammingSolver;
     use crate::structure::make_item;
+    use crate::utils;
+    use rand::Rng;
+
+    // UB-fix running a full kp_greedy for every probe, the reference of the incremental one
+    fn ub_fix_reference(preprocessor: &ProblemPreprocessor, lp_relaxation_result: LPRelaxationResult) -> UBFixResult {
+        let data = preprocessor.problem.data.clone();
+        let x_up = lp_relaxation_result.x_up;
+        let f_0: Vec<(usize, usize)> = lp_relaxation_result.f_0;
+        let mut f_1: Vec<(usize, usize)> = vec![];
+        let mut v_low_best = lp_relaxation_result.v_low;
+        let mut x_best = lp_relaxation_result.x.clone();
+        for index in 0..data.len() {
+            let mut temp_relaxed = lp_relaxation_result.relaxed.clone();
+            let current_set = data[index].clone();
+            if x_up[index][2] == 1.0 {
+                // without the third item the group is the hull of 0, (i, 1) and (i, 2)
+                let first = &current_set[0];
+                let second = &current_set[1];
+
+                // equation 13
+                let is_dominated_by_second = ProblemPreprocessor::is_dominant(second, first);
+                if is_dominated_by_second {
+                    temp_relaxed[index][0].gain = 0;
+                    temp_relaxed[index][0].cost = 0;
+                    temp_relaxed[index][0].ratio = f64::MIN;
+                } else {
+                    temp_relaxed[index][0].gain = first.gain;
+                    temp_relaxed[index][0].cost = first.cost;
+                    temp_relaxed[index][0].ratio = ratio_of(temp_relaxed[index][0].gain, temp_relaxed[index][0].cost);
+                }
+                temp_relaxed[index][1].gain = second.gain - temp_relaxed[index][0].gain;
+                temp_relaxed[index][1].cost = second.cost - temp_relaxed[index][0].cost;
+                temp_relaxed[index][1].ratio = ratio_of(temp_relaxed[index][1].gain, temp_relaxed[index][1].cost);
+                temp_relaxed[index][2].gain = 0;
+                temp_relaxed[index][2].cost = 0;
+                temp_relaxed[index][2].ratio = f64::MIN;
+
+                let res_i = preprocessor.kp_greedy(temp_relaxed, f_0.clone());
+                if res_i.v_low >= v_low_best {
+                    v_low_best = res_i.v_low;
+                    x_best = res_i.x;
+                }
+                if res_i.v_up.ceil() <= v_low_best {
+                    f_1.push((index, 1));
+                }
+            }
+        }
+
+        UBFixResult::new(f_1, x_best, v_low_best)
+    }
 
     fn make_problem() -> Problem {
         let capacity = 50;
@@ -260,9 +369,7 @@ mod tests {
             vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
             vec![make_item(15, 7), make_item(25, 12), make_item(40, 15)],
         ];
-        let size = data.len() as i32;
-
-        Problem { capacity, data, size }
+        Problem::new(capacity, data)
     }
 
     fn make_problem_low_capacity() -> Problem {
@@ -273,9 +380,11 @@ mod tests {
             vec![make_item(20, 1), make_item(35, 35), make_item(55, 35)],
             vec![make_item(11, 3), make_item(22, 15), make_item(33, 16)],
         ];
-        let size = data.len() as i32;
+        Problem::new(capacity, data)
+    }
 
-        Problem { capacity, data, size }
+    fn make_problem_from(capacity: u32, data: Vec<Vec<Item>>) -> Problem {
+        Problem::new(capacity, data)
     }
 
     fn make_test_problem() -> Problem {
@@ -284,9 +393,7 @@ mod tests {
             vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
             vec![make_item(15, 7), make_item(25, 12), make_item(40, 15)],
         ];
-        let size = data.len() as i32;
-
-        Problem { capacity, data, size }
+        Problem::new(capacity, data)
     }
 
     #[test]
@@ -297,9 +404,83 @@ mod tests {
 
         // Check the results
         assert_eq!(f_0.len(), 4);
+        let rules: Vec<_> = f_0.iter().map(|(_, rule)| *rule).collect();
+        assert_eq!(rules, vec![DominanceRule::Eq13, DominanceRule::Eq16, DominanceRule::Eq14, DominanceRule::Eq16]);
         assert_eq!(relaxed_response.len(), 2);
     }
 
+    // the relaxation and UB-fix corrections, independent of the solvers using their bounds: the
+    // third increment, the greedy solution, the UB-fix start and the order of x and x_up
+    #[test]
+    fn test_third_increment_starts_from_second_item() {
+        // no item is dominated, the increments are (10, 5), (6, 4) and (4, 4)
+        let problem = make_problem_from(20, vec![vec![make_item(10, 5), make_item(16, 9), make_item(20, 13)]]);
+        let (f_0, relaxed) = ProblemPreprocessor::new(&problem).lp_relaxation_eliminate_by_dominance();
+        assert!(f_0.is_empty());
+        let increments: Vec<_> = relaxed[0].iter().map(|item| (item.gain, item.cost)).collect();
+        assert_eq!(increments, vec![(10, 5), (6, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn test_greedy_solutions_are_worth_their_bounds() {
+        let mut rng = utils::make_rng(7);
+        for _ in 0..100 {
+            let m = rng.gen_range(1..8);
+            let data: Vec<Vec<Item>> = (0..m).map(|_| {
+                let (c1, c2) = (rng.gen_range(5..30), rng.gen_range(31..60));
+                let (g1, g2) = (rng.gen_range(1..60), rng.gen_range(1..60));
+                vec![make_item(g1, c1), make_item(g2, c2), make_item(g1 + g2, c2 + rng.gen_range(1..c1))]
+            }).collect();
+            let total: i64 = data.iter().map(|group| group[2].cost).sum();
+            let problem = make_problem_from(rng.gen_range(0..total as u32), data);
+            let optimum = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap().fitness as f64;
+            let value_of = |x: &[[f64; 3]]| {
+                let (mut gain, mut cost) = (0, 0);
+                for (i, x_i) in x.iter().enumerate() {
+                    assert!(x_i.iter().filter(|v| **v == 1.0).count() <= 1);
+                    if let Some(k) = x_i.iter().position(|v| *v == 1.0) {
+                        gain += problem.data[i][k].gain;
+                        cost += problem.data[i][k].cost;
+                    }
+                }
+                assert!(cost <= problem.capacity as i64);
+                gain as f64
+            };
+
+            // the greedy keeps the items it counts and never picks a dominated one
+            let preprocessor = ProblemPreprocessor::new(&problem);
+            let relaxation = preprocessor.lp_relaxation();
+            assert_eq!(value_of(&relaxation.x), relaxation.v_low);
+            assert!(relaxation.f_0.iter().all(|(i, k)| relaxation.x[*i][*k] == 0.0));
+
+            // the UB-fix starts from the greedy value and never goes past the optimum
+            let ub_fix = preprocessor.ub_fix(relaxation.clone());
+            assert_eq!(value_of(&ub_fix.x_best), ub_fix.v_best);
+            assert!(relaxation.v_low <= ub_fix.v_best && ub_fix.v_best <= optimum);
+        }
+    }
+
+    #[test]
+    fn test_relaxation_keeps_integer_and_fractional_solutions_apart() {
+        for problem in [make_problem(), make_problem_low_capacity()] {
+            let relaxation = ProblemPreprocessor::new(&problem).lp_relaxation();
+            assert!(relaxation.x.iter().flatten().all(|v| *v == 0.0 || *v == 1.0));
+
+            // x_up weighs the items of every group, the break group mixing two of them
+            let (mut gain, mut cost) = (0.0, 0.0);
+            for (i, x_i) in relaxation.x_up.iter().enumerate() {
+                for (k, weight) in x_i.iter().enumerate() {
+                    gain += weight * problem.data[i][k].gain as f64;
+                    cost += weight * problem.data[i][k].cost as f64;
+                }
+            }
+            assert!((gain - relaxation.v_up).abs() < 1e-9);
+            assert!(cost <= problem.capacity as f64 + 1e-9);
+        }
+        let low = ProblemPreprocessor::new(&make_problem_low_capacity()).lp_relaxation();
+        assert!(low.x_up.iter().flatten().any(|v| *v > 0.0 && *v < 1.0));
+    }
+
     #[test]
     fn test_lp_relaxation() {
         let problem = make_problem();
@@ -321,5 +502,67 @@ mod tests {
         let mut preprocessor = ProblemPreprocessor::new(&problem);
         preprocessor.process_problem();
     }
+
+    #[test]
+    fn test_ub_fix_matches_reference() {
+        let mut rng = utils::make_rng(11);
+        for instance in 0..60 {
+            let m = rng.gen_range(1..40);
+            let data: Vec<Vec<Item>> = (0..m).map(|_| {
+                let (c1, c2) = (rng.gen_range(5..50), rng.gen_range(5..50));
+                let (g1, g2) = (rng.gen_range(1..60), rng.gen_range(1..60));
+                // every third instance keeps the raw order of the costs
+                let (c1, c2) = if instance % 3 == 0 || c1 == c2 { (c1, c2 + 1) } else { (c1.min(c2), c1.max(c2)) };
+                vec![make_item(g1, c1), make_item(g2, c2), make_item(g1 + g2, c1.max(c2) + rng.gen_range(1..c1.min(c2)))]
+            }).collect();
+            let total: i64 = data.iter().map(|group| group[2].cost).sum();
+            let problem = make_problem_from(rng.gen_range(0..total as u32), data);
+
+            let preprocessor = ProblemPreprocessor::new(&problem);
+            let relaxation = preprocessor.lp_relaxation();
+            let expected = ub_fix_reference(&preprocessor, relaxation.clone());
+            let result = preprocessor.ub_fix(relaxation);
+            assert_eq!(result.f_1, expected.f_1, "instance {}", instance);
+            assert_eq!(result.x_best, expected.x_best, "instance {}", instance);
+            assert_eq!(result.v_best, expected.v_best, "instance {}", instance);
+        }
+    }
+
+    #[test]
+    fn test_dantzig_bound() {
+        for problem in [make_problem(), make_problem_low_capacity()] {
+            let optimum = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap().fitness as f64;
+            let preprocessor = ProblemPreprocessor::new(&problem);
+            assert!(preprocessor.dantzig_bound() >= optimum);
+            assert!((preprocessor.dantzig_bound() - preprocessor.lp_relaxation().v_up).abs() < 1e-9);
+        }
+
+        // the first item is never on the hull and the bound ignores the order of the items
+        let problem = make_problem_from(10, vec![vec![make_item(30, 14), make_item(2, 5), make_item(20, 10)]]);
+        assert!((ProblemPreprocessor::new(&problem).dantzig_bound() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_process_problem_bounds_enclose_optimum() {
+        for problem in [make_problem(), make_problem_low_capacity()] {
+            let optimum = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap().fitness as f64;
+            let result = ProblemPreprocessor::new(&problem).process_problem();
+
+            assert!(result.relaxation_result.v_up >= optimum);
+            assert!(result.relaxation_result.v_low <= optimum);
+            assert!(result.ub_fix_result.v_best <= optimum);
+
+            // x_best is feasible and worth v_best
+            let (mut gain, mut cost) = (0, 0);
+            for (i, x_i) in result.ub_fix_result.x_best.iter().enumerate() {
+                if let Some(k) = x_i.iter().position(|v| *v == 1.0) {
+                    gain += problem.data[i][k].gain;
+                    cost += problem.data[i][k].cost;
+                }
+            }
+            assert!(cost <= problem.capacity as i64);
+            assert_eq!(gain as f64, result.ub_fix_result.v_best);
+        }
+    }
 }
 
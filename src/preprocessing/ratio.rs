@@ -0,0 +1,156 @@
+use crate::structure::item::Item;
+use crate::structure::item_preprocessing::ItemPreprocessing;
+use std::cmp::Ordering;
+
+/// Exact quotient `gain / cost` of two integers, compared by cross-multiplication in `i128`.
+///
+/// The cost is kept non negative. A zero cost gives +inf or -inf following the sign of the gain,
+/// and `0 / 0`, the increment between two identical items, ranks as 0: it adds nothing.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Ratio {
+    gain: i64,
+    cost: i64,
+}
+
+impl Ratio {
+    pub(crate) fn new(gain: i64, cost: i64) -> Self {
+        match (gain, cost) {
+            (0, 0) => Ratio { gain: 0, cost: 1 },
+            (gain, cost) if cost < 0 => Ratio { gain: -gain, cost: -cost },
+            (gain, cost) => Ratio { gain, cost },
+        }
+    }
+
+    /// Increment from `from` to `to`.
+    pub(crate) fn between(to: &Item, from: &Item) -> Self {
+        Ratio::new(to.gain - from.gain, to.cost - from.cost)
+    }
+
+    pub(crate) fn of(item: &Item) -> Self {
+        Ratio::new(item.gain, item.cost)
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.cost, other.cost) {
+            (0, 0) => self.gain.signum().cmp(&other.gain.signum()),
+            (0, _) => self.gain.signum().cmp(&0),
+            (_, 0) => 0.cmp(&other.gain.signum()),
+            _ => (self.gain as i128 * other.cost as i128).cmp(&(other.gain as i128 * self.cost as i128)),
+        }
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ratio {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ratio {}
+
+/// Float ratio stored in the relaxation, 0 for `0 / 0` instead of NaN.
+pub(crate) fn ratio_of(gain: i64, cost: i64) -> f64 {
+    if gain == 0 && cost == 0 {
+        0.0
+    } else {
+        gain as f64 / cost as f64
+    }
+}
+
+/// Order of the greedy: decreasing exact ratio with the removed items last, ties kept in group
+/// order.
+pub(crate) fn greedy_order(a: &ItemPreprocessing, b: &ItemPreprocessing) -> Ordering {
+    let by_ratio = match (a.ratio == f64::MIN, b.ratio == f64::MIN) {
+        (false, false) => Ratio::new(b.gain, b.cost).cmp(&Ratio::new(a.gain, a.cost)),
+        (removed_a, removed_b) => removed_a.cmp(&removed_b),
+    };
+    by_ratio.then((a.set_index, a.inner_index).cmp(&(b.set_index, b.inner_index)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessing::ProblemPreprocessor;
+    use crate::structure::{make_item, make_problem};
+    use proptest::prelude::*;
+
+    // the float quotients are exact enough to decide comparisons of values this small
+    const VALUE: std::ops::Range<i64> = -1000..1000;
+
+    fn non_zero() -> impl Strategy<Value = i64> {
+        VALUE.prop_filter("non zero", |value| *value != 0)
+    }
+
+    #[test]
+    fn test_zero_cost_differences() {
+        assert!(Ratio::new(5, 0) > Ratio::new(i64::MAX, 1));
+        assert!(Ratio::new(-5, 0) < Ratio::new(i64::MIN, 1));
+        assert_eq!(Ratio::new(0, 0), Ratio::new(0, 7));
+        assert_eq!(Ratio::new(3, 0), Ratio::new(1, 0));
+
+        // identical items and equal costs used to give NaN and panic in the greedy sort
+        let problem = make_problem(30, vec![
+            vec![make_item(10, 5), make_item(10, 5), make_item(10, 5)],
+            vec![make_item(15, 7), make_item(20, 7), make_item(20, 12)],
+        ], 2);
+        let result = ProblemPreprocessor::new(&problem).process_problem();
+        assert!(result.relaxation_result.f_0.contains(&(0, 0)));
+        assert!(result.relaxation_result.f_0.contains(&(1, 0)));
+        assert!(result.relaxation_result.v_up >= 30.0);
+    }
+
+    proptest! {
+        #[test]
+        fn test_ratio_order_matches_float_quotients(a in VALUE, b in non_zero(), c in VALUE, d in non_zero()) {
+            let float = (a as f64 / b as f64).partial_cmp(&(c as f64 / d as f64)).unwrap();
+            prop_assert_eq!(Ratio::new(a, b).cmp(&Ratio::new(c, d)), float);
+        }
+
+        #[test]
+        fn test_dominance_matches_float_path(
+            items in prop::collection::vec((VALUE, VALUE), 3),
+        ) {
+            let [first, second, third] = [0, 1, 2].map(|k| make_item(items[k].0, items[k].1));
+            let float_dominant = |dominant: &Item, to_check: &Item| {
+                let difference = (dominant.gain - to_check.gain) as f64 / (dominant.cost - to_check.cost) as f64;
+                difference >= to_check.gain as f64 / to_check.cost as f64
+            };
+            let float_ranged = |up: &Item, down: &Item, to_check: &Item| {
+                (up.gain - to_check.gain) as f64 / (up.cost - to_check.cost) as f64
+                    >= (to_check.gain - down.gain) as f64 / (to_check.cost - down.cost) as f64
+            };
+            let defined = |x: &Item, y: &Item| x.cost != y.cost;
+
+            if defined(&second, &first) && first.cost != 0 {
+                prop_assert_eq!(ProblemPreprocessor::is_dominant(&second, &first), float_dominant(&second, &first));
+            }
+            if defined(&third, &second) && second.cost != 0 {
+                prop_assert_eq!(ProblemPreprocessor::is_dominant(&third, &second), float_dominant(&third, &second));
+            }
+            if defined(&third, &second) && defined(&second, &first) {
+                prop_assert_eq!(ProblemPreprocessor::is_ranged_dominated(&third, &first, &second), float_ranged(&third, &first, &second));
+            }
+        }
+
+        #[test]
+        fn test_preprocessing_never_panics(
+            groups in prop::collection::vec(prop::collection::vec((0..50i64, 0..50i64), 3), 1..8),
+            capacity in 0..200u32,
+        ) {
+            let data = groups.iter()
+                .map(|group| group.iter().map(|(gain, cost)| make_item(*gain, *cost)).collect())
+                .collect();
+            let problem = make_problem(capacity, data, groups.len() as i32);
+            let result = ProblemPreprocessor::new(&problem).process_problem();
+            prop_assert!(result.ub_fix_result.v_best >= result.relaxation_result.v_low);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::dp::DynamicProgrammingSolver;
+use crate::preprocessing::{PreprocessingResult, ProblemPreprocessor};
+use crate::structure::problem::Problem;
+use crate::structure::reduced_cost_result::ReducedCostResult;
+use log::debug;
+
+// instances small enough for the debug verification of the fixings
+const VERIFIED_GROUPS: usize = 20;
+const VERIFIED_MEMORY: usize = 1 << 20;
+
+impl ProblemPreprocessor<'_> {
+    /// Reduced-cost fixing at the multiplier `multiplier` of the Lagrangian relaxation.
+    ///
+    /// Forcing group `i` to an option lowers the Lagrangian bound by the reduced cost of the
+    /// option, the gap between its Lagrangian profit and the best one of the group. Options whose
+    /// bound drops below `v_best + 1` are taken by no solution better than `v_best`, gains being
+    /// integers, and a group left with a single item takes it.
+    pub(super) fn reduced_cost_fixing(&self, multiplier: f64, v_best: f64) -> ReducedCostResult {
+        let capacity = self.problem.capacity as i64;
+        let profit = |gain: i64, cost: i64| gain as f64 - multiplier * cost as f64;
+        let best_profits: Vec<f64> = self.problem.data.iter().map(|group| {
+            group.iter().filter(|item| item.cost <= capacity).map(|item| profit(item.gain, item.cost)).fold(0.0, f64::max)
+        }).collect();
+        let bound = multiplier * capacity as f64 + best_profits.iter().sum::<f64>();
+        let threshold = v_best + 1.0 - (1e-6 + 1e-9 * bound.abs());
+        // the best option of every group keeps the bound, none is left when the UB-fix is optimal
+        if bound < threshold {
+            return ReducedCostResult::new(vec![], vec![], multiplier);
+        }
+
+        let mut f_0 = vec![];
+        let mut f_1 = vec![];
+        for (i, group) in self.problem.data.iter().enumerate() {
+            let others = bound - best_profits[i];
+            let excluded: Vec<bool> = group.iter()
+                .map(|item| item.cost > capacity || others + profit(item.gain, item.cost) < threshold)
+                .collect();
+            f_0.extend((0..group.len()).filter(|k| excluded[*k]).map(|k| (i, k)));
+            let mut left = (0..group.len()).filter(|k| !excluded[*k]);
+            if let (Some(k), None, true) = (left.next(), left.next(), others < threshold) {
+                f_1.push((i, k));
+            }
+        }
+        debug!("Reduced-cost fixing with multiplier {}: {} items fixed to 0, {} groups fixed", multiplier, f_0.len(), f_1.len());
+        ReducedCostResult::new(f_0, f_1, multiplier)
+    }
+
+    fn is_verifiable(&self) -> bool {
+        self.problem.data.len() <= VERIFIED_GROUPS && DynamicProgrammingSolver::required_memory(self.problem) <= VERIFIED_MEMORY
+    }
+
+    /// Checks with the dynamic programming that no solution better than the UB-fix one is cut off
+    /// by the fixings, on instances of up to 20 groups.
+    pub fn verify_fixings(&self, preprocessing_result: &PreprocessingResult) -> Result<(), String> {
+        if !self.is_verifiable() {
+            return Err(format!("only instances of up to {} groups with a small capacity can be verified", VERIFIED_GROUPS));
+        }
+        let v_best = preprocessing_result.ub_fix_result.v_best;
+        let reduced_cost_result = &preprocessing_result.reduced_cost_result;
+        let mut cut_off: Vec<(&str, usize, usize)> = vec![];
+        for (group, item) in &reduced_cost_result.f_0 {
+            cut_off.push(("reduced-cost f_0", *group, item + 1));
+        }
+        for (group, item) in &reduced_cost_result.f_1 {
+            cut_off.extend((0..=3).filter(|option| *option != item + 1).map(|option| ("reduced-cost f_1", *group, option)));
+        }
+        // the UB-fix relies on the dominance rules, which expect items ordered by cost
+        let ordered = self.problem.data.iter().all(|g| g[0].cost < g[1].cost && g[1].cost < g[2].cost);
+        if ordered {
+            for (group, _) in &preprocessing_result.ub_fix_result.f_1 {
+                cut_off.extend((0..3).map(|option| ("UB-fix f_1", *group, option)));
+            }
+        }
+
+        for (pass, group, option) in cut_off {
+            if let Some(value) = self.forced_optimum(group, option) {
+                if value as f64 > v_best {
+                    return Err(format!("{} cuts off group {} option {}, reaching {} above the UB-fix value {}", pass, group, option, value, v_best));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // optimum of the problem where `group` takes `option`, none when the option does not fit
+    fn forced_optimum(&self, group: usize, option: usize) -> Option<i64> {
+        let (gain, cost) = match option {
+            0 => (0, 0),
+            k => (self.problem.data[group][k - 1].gain, self.problem.data[group][k - 1].cost),
+        };
+        if cost > self.problem.capacity as i64 {
+            return None;
+        }
+        let mut data = self.problem.data.clone();
+        data.remove(group);
+        let rest = Problem::new((self.problem.capacity as i64 - cost) as u32, data);
+        let solution = DynamicProgrammingSolver::new(rest, usize::MAX).solve().ok()?;
+        Some(solution.fitness + gain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+    use crate::utils;
+    use rand::Rng;
+
+    #[test]
+    fn test_reduced_cost_fixings_keep_improving_solutions() {
+        let mut rng = utils::make_rng(8);
+        let mut fixed = 0;
+        for _ in 0..40 {
+            let m: usize = rng.gen_range(2..12);
+            let data: Vec<Vec<_>> = (0..m).map(|_| {
+                let c1 = rng.gen_range(5..30);
+                let c2 = c1 + rng.gen_range(1..30);
+                let (g1, g2) = (rng.gen_range(1..60), rng.gen_range(1..60));
+                vec![make_item(g1, c1), make_item(g2, c2), make_item(g1 + g2, c2 + rng.gen_range(1..c1))]
+            }).collect();
+            let total: i64 = data.iter().map(|g| g[2].cost).sum();
+            let problem = make_problem(rng.gen_range(total as u32 / 4..total as u32), data, m as i32);
+
+            let mut preprocessor = ProblemPreprocessor::new(&problem);
+            let result = preprocessor.process_problem();
+            fixed += result.reduced_cost_result.f_0.len();
+            assert_eq!(preprocessor.verify_fixings(&result), Ok(()));
+        }
+        assert!(fixed > 0);
+    }
+
+    #[test]
+    fn test_verify_fixings_refuses_large_instances() {
+        let data = vec![vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)]; 21];
+        let problem = make_problem(100, data, 21);
+        let mut preprocessor = ProblemPreprocessor::new(&problem);
+        let result = preprocessor.process_problem();
+        assert!(preprocessor.verify_fixings(&result).is_err());
+    }
+}
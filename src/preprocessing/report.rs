@@ -0,0 +1,211 @@
+use crate::preprocessing::{PreprocessingResult, ProblemPreprocessor};
+use crate::structure::problem::Problem;
+use crate::structure::relaxation_result::DominanceRule;
+use serde::Serialize;
+use std::fmt;
+
+/// Item removed by the LP dominance, `item` counts from 1 like the choices.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DominatedItem {
+    pub group: usize,
+    pub item: usize,
+    pub rule: DominanceRule,
+}
+
+/// Item fixed by the reduced-cost fixing, `item` counts from 1 like the choices.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FixedItem {
+    pub group: usize,
+    pub item: usize,
+}
+
+/// Summary of the preprocessing of a problem, printed by the `preprocess` subcommand.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{Item, PreprocessingReport, Problem, ProblemPreprocessor};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+/// let report = PreprocessingReport::new(&problem, &preprocessing);
+/// assert!(report.lp_bound() >= report.greedy_lower_bound());
+/// assert!(report.lagrangian_bound() >= report.lagrangian_value() as f64);
+/// assert!(report.to_json().contains("\"dominated\""));
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct PreprocessingReport {
+    pub(crate) groups: usize,
+    pub(crate) capacity: u32,
+    pub(crate) lp_bound: f64,
+    pub(crate) dantzig_bound: f64,
+    pub(crate) greedy_lower_bound: f64,
+    pub(crate) lagrangian_bound: f64,
+    pub(crate) lagrangian_value: i64,
+    pub(crate) ub_fix_value: f64,
+    pub(crate) ub_fix_choices: Vec<usize>,
+    pub(crate) fixed_groups: Vec<usize>,
+    pub(crate) reduced_cost_items: Vec<FixedItem>,
+    pub(crate) reduced_cost_groups: Vec<FixedItem>,
+    pub(crate) dominated: Vec<DominatedItem>,
+}
+
+impl PreprocessingReport {
+    pub fn new(problem: &Problem, preprocessing_result: &PreprocessingResult) -> Self {
+        let relaxation_result = &preprocessing_result.relaxation_result;
+        let ub_fix_result = &preprocessing_result.ub_fix_result;
+        let lagrangian_result = &preprocessing_result.lagrangian_result;
+        let reduced_cost_result = &preprocessing_result.reduced_cost_result;
+        let fixed_items = |fixed: &[(usize, usize)]| fixed.iter().map(|(group, item)| FixedItem { group: *group, item: item + 1 }).collect();
+        let dominated = relaxation_result.f_0.iter().zip(&relaxation_result.rules)
+            .map(|((group, item), rule)| DominatedItem { group: *group, item: item + 1, rule: *rule })
+            .collect();
+        PreprocessingReport {
+            groups: problem.data.len(),
+            capacity: problem.capacity,
+            lp_bound: relaxation_result.v_up,
+            dantzig_bound: ProblemPreprocessor::new(problem).dantzig_bound(),
+            greedy_lower_bound: relaxation_result.v_low,
+            lagrangian_bound: lagrangian_result.bound,
+            lagrangian_value: lagrangian_result.value,
+            ub_fix_value: ub_fix_result.v_best,
+            ub_fix_choices: ub_fix_result.choices(),
+            fixed_groups: ub_fix_result.f_1.iter().map(|(group, _)| *group).collect(),
+            reduced_cost_items: fixed_items(&reduced_cost_result.f_0),
+            reduced_cost_groups: fixed_items(&reduced_cost_result.f_1),
+            dominated,
+        }
+    }
+
+    /// Upper bound of the LP relaxation after the dominance.
+    pub fn lp_bound(&self) -> f64 {
+        self.lp_bound
+    }
+
+    /// Value of the greedy solution built from the LP relaxation.
+    pub fn greedy_lower_bound(&self) -> f64 {
+        self.greedy_lower_bound
+    }
+
+    /// Bound of the Lagrangian relaxation of the capacity constraint.
+    pub fn lagrangian_bound(&self) -> f64 {
+        self.lagrangian_bound
+    }
+
+    /// Value of the best solution found by the Lagrangian heuristic.
+    pub fn lagrangian_value(&self) -> i64 {
+        self.lagrangian_value
+    }
+
+    /// Value of the best solution found by the UB-fix.
+    pub fn ub_fix_value(&self) -> f64 {
+        self.ub_fix_value
+    }
+
+    /// Groups fixed to their third item by the UB-fix.
+    pub fn fixed_groups(&self) -> &[usize] {
+        &self.fixed_groups
+    }
+
+    /// Items no solution better than the UB-fix one takes, found by the reduced-cost fixing.
+    pub fn reduced_cost_items(&self) -> &[FixedItem] {
+        &self.reduced_cost_items
+    }
+
+    /// Groups taking a single item in every solution better than the UB-fix one, found by the
+    /// reduced-cost fixing.
+    pub fn reduced_cost_groups(&self) -> &[FixedItem] {
+        &self.reduced_cost_groups
+    }
+
+    pub fn dominated(&self) -> &[DominatedItem] {
+        &self.dominated
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+fn join(values: &[usize]) -> String {
+    values.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+impl fmt::Display for PreprocessingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<24} {}", "groups", self.groups)?;
+        writeln!(f, "{:<24} {}", "capacity", self.capacity)?;
+        writeln!(f, "{:<24} {}", "LP bound", self.lp_bound)?;
+        writeln!(f, "{:<24} {}", "Dantzig bound", self.dantzig_bound)?;
+        writeln!(f, "{:<24} {}", "greedy lower bound", self.greedy_lower_bound)?;
+        writeln!(f, "{:<24} {}", "Lagrangian bound", self.lagrangian_bound)?;
+        writeln!(f, "{:<24} {}", "Lagrangian heuristic", self.lagrangian_value)?;
+        writeln!(f, "{:<24} {}", "UB-fix value", self.ub_fix_value)?;
+        writeln!(f, "{:<24} {}", "UB-fix choices", join(&self.ub_fix_choices))?;
+        writeln!(f, "{:<24} {}", "fixed groups", self.fixed_groups.len())?;
+        if !self.fixed_groups.is_empty() {
+            writeln!(f, "{:<24} {}", "", join(&self.fixed_groups))?;
+        }
+        writeln!(f, "{:<24} {}", "reduced-cost items", self.reduced_cost_items.len())?;
+        writeln!(f, "{:<24} {}", "reduced-cost groups", self.reduced_cost_groups.len())?;
+        writeln!(f, "{:<24} {}", "dominated items", self.dominated.len())?;
+        writeln!(f)?;
+        writeln!(f, "{:>8} {:>6} {:>6}", "group", "item", "rule")?;
+        for dominated in &self.dominated {
+            writeln!(f, "{:>8} {:>6} {:>6}", dominated.group, dominated.item, dominated.rule)?;
+        }
+        for (fixed_items, value) in [(&self.reduced_cost_items, 0), (&self.reduced_cost_groups, 1)] {
+            if fixed_items.is_empty() {
+                continue;
+            }
+            writeln!(f)?;
+            writeln!(f, "{:>8} {:>6} {:>6}", "group", "item", "fixed")?;
+            for fixed in fixed_items {
+                writeln!(f, "{:>8} {:>6} {:>6}", fixed.group, fixed.item, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    #[test]
+    fn test_report_lists_dominated_items_with_their_rule() {
+        let problem = make_problem(50, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(40, 15)],
+        ], 2);
+        let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+        let report = PreprocessingReport::new(&problem, &preprocessing);
+
+        assert_eq!(report.dominated()[0], DominatedItem { group: 0, item: 1, rule: DominanceRule::Eq13 });
+        assert_eq!(report.dominated()[2], DominatedItem { group: 1, item: 1, rule: DominanceRule::Eq14 });
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["dominated"][1]["rule"], "eq16");
+        assert_eq!(json["ub_fix_choices"].as_array().unwrap().len(), 2);
+        assert_eq!(json["lagrangian_value"], 70);
+        assert!(report.to_string().contains("    1      2   eq16"));
+    }
+
+    #[test]
+    fn test_report_lists_fixed_groups_without_fixed_items() {
+        let problem = make_problem(50, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(40, 15)],
+        ], 2);
+        let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+        let mut report = PreprocessingReport::new(&problem, &preprocessing);
+        report.reduced_cost_items.clear();
+        report.reduced_cost_groups = vec![FixedItem { group: 1, item: 2 }];
+
+        let text = report.to_string();
+        assert!(text.contains("   group   item  fixed"));
+        assert!(text.contains("       1      2      1"));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::preprocessing::ratio::{greedy_order, ratio_of};
+use crate::preprocessing::ProblemPreprocessor;
+use crate::structure::fix_result::UBFixResult;
+use crate::structure::item_preprocessing::ItemPreprocessing;
+use crate::structure::problem::Problem;
+use crate::structure::relaxation_result::LPRelaxationResult;
+use log::debug;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// Bounds of the relaxation where one group has lost its third item.
+struct Probe {
+    v_up: f64,
+    v_low: f64,
+}
+
+/// Relaxation sorted once with the prefix sums of its costs and gains. A probe looks up where the
+/// capacity runs out instead of walking the sorted relaxation, only the few items of its group
+/// move.
+struct Probing<'a> {
+    problem: &'a Problem,
+    order: Vec<&'a ItemPreprocessing>,
+    // position in `order` of the items of every group, and of the first one
+    positions: Vec<[usize; 3]>,
+    first_position: Vec<usize>,
+    // the greedy only takes the items before `positive`, the ones of positive ratio
+    positive: usize,
+    // total cost and gain of the items before a position
+    prefix_cost: Vec<i64>,
+    prefix_gain: Vec<i64>,
+    // cost of the items up to a position included, increments of unordered groups may be negative
+    reached_cost: MaxTree,
+    // smallest original cost of the items left from a position, dominated items excluded
+    suffix_min_cost: Vec<i64>,
+}
+
+/// Maximum over ranges of positions, searched for the first position reaching a value in
+/// O(log n).
+struct MaxTree {
+    leaves: usize,
+    max: Vec<i64>,
+}
+
+impl MaxTree {
+    fn new(values: &[i64]) -> Self {
+        let leaves = values.len().next_power_of_two();
+        let mut max = vec![i64::MIN; 2 * leaves];
+        max[leaves..leaves + values.len()].copy_from_slice(values);
+        for node in (1..leaves).rev() {
+            max[node] = max[2 * node].max(max[2 * node + 1]);
+        }
+        MaxTree { leaves, max }
+    }
+
+    /// First position of `from..to` whose value is at least `threshold`.
+    fn first_at_least(&self, from: usize, to: usize, threshold: i64) -> Option<usize> {
+        self.search(1, 0..self.leaves, from..to, threshold)
+    }
+
+    fn search(&self, node: usize, span: Range<usize>, range: Range<usize>, threshold: i64) -> Option<usize> {
+        if span.end <= range.start || range.end <= span.start || self.max[node] < threshold {
+            return None;
+        }
+        if span.len() == 1 {
+            return Some(span.start);
+        }
+        let middle = (span.start + span.end) / 2;
+        self.search(2 * node, span.start..middle, range.clone(), threshold)
+            .or_else(|| self.search(2 * node + 1, middle..span.end, range, threshold))
+    }
+}
+
+/// Walk over the sorted relaxation without the old items of `group`, with its new items merged in.
+#[derive(Clone)]
+struct Merged<'p, 'a> {
+    order: &'p [&'a ItemPreprocessing],
+    group: usize,
+    new_items: &'p [(&'p ItemPreprocessing, usize)],
+    position: usize,
+    next_new: usize,
+}
+
+impl<'p, 'a> Merged<'p, 'a> {
+    // yields the item and the number of items of the sorted relaxation ranked before it
+    fn next(&mut self) -> Option<(&'p ItemPreprocessing, usize)> {
+        while self.position < self.order.len() && self.order[self.position].set_index == self.group {
+            self.position += 1;
+        }
+        match (self.new_items.get(self.next_new), self.order.get(self.position)) {
+            (Some((item, rank)), base) if base.is_none_or(|base| greedy_order(item, base) == Ordering::Less) => {
+                self.next_new += 1;
+                Some((*item, *rank))
+            }
+            (_, Some(base)) => {
+                self.position += 1;
+                Some((*base, self.position - 1))
+            }
+            (_, None) => None,
+        }
+    }
+}
+
+impl<'a> Probing<'a> {
+    fn new(problem: &'a Problem, relaxed: &'a [[ItemPreprocessing; 3]]) -> Self {
+        let mut order: Vec<&ItemPreprocessing> = relaxed.iter().flat_map(|group| group.iter()).collect();
+        order.sort_by(|a, b| greedy_order(a, b));
+
+        let mut positions = vec![[0; 3]; relaxed.len()];
+        for (position, item) in order.iter().enumerate() {
+            positions[item.set_index][item.inner_index] = position;
+        }
+        let first_position = positions.iter().map(|group| group.iter().copied().min().unwrap()).collect();
+        let positive = order.partition_point(|item| item.ratio > 0.0);
+        let mut prefix_cost = vec![0; order.len() + 1];
+        let mut prefix_gain = vec![0; order.len() + 1];
+        for (position, item) in order.iter().enumerate() {
+            prefix_cost[position + 1] = prefix_cost[position] + item.cost;
+            prefix_gain[position + 1] = prefix_gain[position] + item.gain;
+        }
+        let reached_cost = MaxTree::new(&prefix_cost[1..=positive]);
+        let mut suffix_min_cost = vec![i64::MAX; order.len() + 1];
+        for (position, item) in order.iter().enumerate().rev() {
+            suffix_min_cost[position] = suffix_min_cost[position + 1];
+            if item.ratio != f64::MIN {
+                suffix_min_cost[position] = suffix_min_cost[position].min(problem.data[item.set_index][item.inner_index].cost);
+            }
+        }
+        Probing { problem, order, positions, first_position, positive, prefix_cost, prefix_gain, reached_cost, suffix_min_cost }
+    }
+
+    /// Same bounds as `kp_greedy` on the relaxation where `group` takes `items`, fills `x` with
+    /// the greedy solution when given.
+    fn probe(&self, group: usize, items: &[ItemPreprocessing; 3], x: Option<&mut [[f64; 3]]>) -> Probe {
+        let mut new_items: Vec<(&ItemPreprocessing, usize)> = items.iter()
+            .map(|item| (item, self.order.partition_point(|base| greedy_order(base, item) == Ordering::Less)))
+            .collect();
+        new_items.sort_by(|a, b| greedy_order(a.0, b.0));
+        let mut removed = self.positions[group];
+        removed.sort();
+
+        let capacity = self.problem.capacity as i64;
+        // the greedy takes every item before `position` and the first `next_new` new items, the
+        // old items of the group passed so far being removed from the totals
+        let (mut position, mut next_new, mut next_removed) = (0, 0, 0);
+        let (mut removed_cost, mut removed_gain) = (0, 0);
+        let (mut added_cost, mut added_gain) = (0, 0);
+        if capacity > 0 {
+            loop {
+                while next_removed < 3 && removed[next_removed] < position {
+                    next_removed += 1;
+                }
+                let new_rank = new_items.get(next_new).map_or(usize::MAX, |(_, rank)| *rank);
+                let removed_at = removed.get(next_removed).copied().unwrap_or(usize::MAX);
+                let end = new_rank.min(removed_at).min(self.positive);
+                let threshold = capacity + removed_cost - added_cost;
+                if let Some(reached) = self.reached_cost.first_at_least(position, end, threshold) {
+                    position = reached;
+                    break;
+                }
+                position = end;
+                if end == new_rank {
+                    let item = new_items[next_new].0;
+                    if item.ratio <= 0.0 || self.prefix_cost[end] - removed_cost + added_cost + item.cost >= capacity {
+                        break;
+                    }
+                    added_cost += item.cost;
+                    added_gain += item.gain;
+                    next_new += 1;
+                } else if end == self.positive {
+                    break;
+                } else {
+                    removed_cost += self.order[end].cost;
+                    removed_gain += self.order[end].gain;
+                    position = end + 1;
+                }
+            }
+        }
+        let taken_cost = self.prefix_cost[position] - removed_cost + added_cost;
+        let taken_gain = self.prefix_gain[position] - removed_gain + added_gain;
+        let mut remaining_capacity = capacity - taken_cost;
+        let mut v_up = taken_gain as f64;
+        let mut v_low = 0.0;
+        let mut low_capacity = 0;
+
+        let merged = Merged { order: &self.order, group, new_items: &new_items, position, next_new };
+        let mut fill = merged.clone();
+        let boundary = match fill.clone().next() {
+            Some((item, rank)) => {
+                if remaining_capacity > 0 && item.ratio > 0.0 {
+                    // the break item
+                    v_low = v_up;
+                    low_capacity = remaining_capacity;
+                    v_up += item.gain as f64 * (remaining_capacity as f64 / item.cost as f64);
+                    remaining_capacity = 0;
+                }
+                rank
+            }
+            None => self.order.len(),
+        };
+        if remaining_capacity > 0 {
+            v_low = v_up;
+            low_capacity = remaining_capacity;
+        }
+        let group_taken = next_new > 0;
+
+        let mut x = x;
+        if let Some(x) = x.as_deref_mut() {
+            // every item before the boundary is taken, the last one of a group replaces the others
+            let mut taken = Merged { position: 0, next_new: 0, ..merged };
+            let count = position - removed.iter().filter(|p| **p < position).count() + next_new;
+            for _ in 0..count {
+                let (item, _) = taken.next().unwrap();
+                x[item.set_index].fill(0.0);
+                x[item.set_index][item.inner_index] = 1.0;
+            }
+        }
+
+        // fill the capacity left by the break item with whole items of the empty groups
+        let mut filled: Vec<usize> = vec![];
+        loop {
+            let new_min_cost = merged_min_cost(&fill, self.problem);
+            if low_capacity < self.suffix_min_cost[fill.position].min(new_min_cost) {
+                break;
+            }
+            let Some((item, _)) = fill.next() else {
+                break;
+            };
+            if item.ratio == f64::MIN {
+                continue;
+            }
+            let i = item.set_index;
+            let original = &self.problem.data[i][item.inner_index];
+            let empty = if i == group { !group_taken } else { self.first_position[i] >= boundary };
+            if low_capacity >= original.cost && empty && !filled.contains(&i) {
+                if let Some(x) = x.as_deref_mut() {
+                    x[i][item.inner_index] = 1.0;
+                }
+                v_low += original.gain as f64;
+                low_capacity -= original.cost;
+                filled.push(i);
+            }
+        }
+
+        Probe { v_up, v_low }
+    }
+}
+
+// smallest original cost of the new items not yet merged
+fn merged_min_cost(merged: &Merged, problem: &Problem) -> i64 {
+    merged.new_items[merged.next_new..].iter()
+        .filter(|(item, _)| item.ratio != f64::MIN)
+        .map(|(item, _)| problem.data[item.set_index][item.inner_index].cost)
+        .min()
+        .unwrap_or(i64::MAX)
+}
+
+impl ProblemPreprocessor<'_> {
+    // without the third item the group is the hull of 0, (i, 1) and (i, 2)
+    fn without_third_item(&self, index: usize, relaxed: &[ItemPreprocessing; 3]) -> [ItemPreprocessing; 3] {
+        let first = &self.problem.data[index][0];
+        let second = &self.problem.data[index][1];
+        let mut items = relaxed.clone();
+
+        // equation 13
+        if ProblemPreprocessor::is_dominant(second, first) {
+            items[0].gain = 0;
+            items[0].cost = 0;
+            items[0].ratio = f64::MIN;
+        } else {
+            items[0].gain = first.gain;
+            items[0].cost = first.cost;
+            items[0].ratio = ratio_of(items[0].gain, items[0].cost);
+        }
+        items[1].gain = second.gain - items[0].gain;
+        items[1].cost = second.cost - items[0].cost;
+        items[1].ratio = ratio_of(items[1].gain, items[1].cost);
+        items[2].gain = 0;
+        items[2].cost = 0;
+        items[2].ratio = f64::MIN;
+        items
+    }
+
+    /// Fixes the third item of the groups whose relaxation without it can not beat the best
+    /// known solution.
+    ///
+    /// The groups are probed in parallel, their results are then applied in group order so the
+    /// incumbent, hence the fixings, do not depend on the scheduling.
+    pub(super) fn ub_fix(&self, lp_relaxation_result: LPRelaxationResult) -> UBFixResult {
+        let relaxed = &lp_relaxation_result.relaxed;
+        let probing = Probing::new(self.problem, relaxed);
+        let candidates: Vec<usize> = (0..self.problem.data.len()).filter(|i| lp_relaxation_result.x_up[*i][2] == 1.0).collect();
+        let probes: Vec<Probe> = candidates.par_iter()
+            .map(|index| probing.probe(*index, &self.without_third_item(*index, &relaxed[*index]), None))
+            .collect();
+
+        let mut f_1: Vec<(usize, usize)> = vec![];
+        let mut v_low_best = lp_relaxation_result.v_low;
+        let mut best_probe = None;
+        for (index, probe) in candidates.iter().zip(&probes) {
+            if probe.v_low >= v_low_best {
+                v_low_best = probe.v_low;
+                best_probe = Some(*index);
+            }
+            if probe.v_up.ceil() <= v_low_best {
+                f_1.push((*index, 1));
+            } else {
+                debug!("UB-fix can not fix group {}, bound without its third item: {}", index, probe.v_up);
+            }
+        }
+
+        let x_best = match best_probe {
+            Some(index) => {
+                let mut x = vec![[0.0; 3]; self.problem.data.len()];
+                probing.probe(index, &self.without_third_item(index, &relaxed[index]), Some(&mut x));
+                x
+            }
+            None => lp_relaxation_result.x,
+        };
+        UBFixResult::new(f_1, x_best, v_low_best)
+    }
+}
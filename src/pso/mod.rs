@@ -0,0 +1,252 @@
+use crate::error::SolverError;
+use crate::genetic::operators;
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use crate::utils;
+use log::{debug, info};
+use rand::Rng;
+use std::sync::Arc;
+
+/// Parameters of the binary particle swarm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PsoSettings {
+    pub(crate) inertia: f64,
+    pub(crate) cognitive: f64,
+    pub(crate) social: f64,
+    pub(crate) max_velocity: f64,
+}
+
+impl PsoSettings {
+    pub fn new() -> Self {
+        PsoSettings::default()
+    }
+
+    pub fn inertia(mut self, inertia: f64) -> Self {
+        self.inertia = inertia;
+        self
+    }
+
+    /// Attraction to the best position of the particle.
+    pub fn cognitive(mut self, cognitive: f64) -> Self {
+        self.cognitive = cognitive;
+        self
+    }
+
+    /// Attraction to the best position of the swarm.
+    pub fn social(mut self, social: f64) -> Self {
+        self.social = social;
+        self
+    }
+
+    /// Velocities are clamped to `[-max_velocity, max_velocity]`, keeping the sigmoid away from 0 and 1.
+    pub fn max_velocity(mut self, max_velocity: f64) -> Self {
+        self.max_velocity = max_velocity;
+        self
+    }
+
+    fn validate(&self) -> Result<(), SolverError> {
+        let weights = [("inertia", self.inertia), ("cognitive", self.cognitive), ("social", self.social)];
+        if let Some((name, value)) = weights.iter().find(|(_, value)| !(*value >= 0.0 && value.is_finite())) {
+            return Err(SolverError::InvalidPsoSettings(format!("{} weight {} must not be negative", name, value)));
+        }
+        if !(self.max_velocity > 0.0 && self.max_velocity.is_finite()) {
+            return Err(SolverError::InvalidPsoSettings(format!("maximum velocity {} must be positive", self.max_velocity)));
+        }
+        Ok(())
+    }
+}
+
+impl Default for PsoSettings {
+    fn default() -> Self {
+        PsoSettings {
+            inertia: 0.7,
+            cognitive: 1.5,
+            social: 1.5,
+            max_velocity: 4.0,
+        }
+    }
+}
+
+/// Binary particle swarm optimization.
+///
+/// A group is encoded by two bits, the first item, the second item and both of them, i.e. the
+/// third item, giving the genes 1, 2 and 3. Each bit has a velocity and is set with the
+/// probability given by the sigmoid of its velocity, positions are then repaired with the rule of
+/// the genetic algorithm. The swarm stops after `stagnation_limit` iterations without
+/// improvement of its best position.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{Item, ParticleSwarm, Problem};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let solution = ParticleSwarm::new(problem).swarm_size(20).seed(1).solve().unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// ```
+pub struct ParticleSwarm {
+    problem: Arc<Problem>,
+    settings: PsoSettings,
+    swarm_size: u32,
+    seed: u64,
+    stagnation_limit: u8,
+    cancellation: Cancellation,
+}
+
+struct Particle {
+    bits: Vec<bool>,
+    velocity: Vec<f64>,
+    best_bits: Vec<bool>,
+    best: i64,
+}
+
+fn sigmoid(velocity: f64) -> f64 {
+    1.0 / (1.0 + (-velocity).exp())
+}
+
+fn bits_to_genes(bits: &[bool]) -> Vec<usize> {
+    bits.chunks(2).map(|pair| pair[0] as usize + 2 * pair[1] as usize).collect()
+}
+
+fn genes_to_bits(genes: &[usize]) -> Vec<bool> {
+    genes.iter().flat_map(|gene| [gene & 1 == 1, gene & 2 == 2]).collect()
+}
+
+impl ParticleSwarm {
+    pub fn new(problem: impl Into<Arc<Problem>>) -> Self {
+        ParticleSwarm {
+            problem: problem.into(),
+            settings: PsoSettings::default(),
+            swarm_size: 100,
+            seed: 0,
+            stagnation_limit: 128,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    pub fn settings(mut self, settings: PsoSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn swarm_size(mut self, swarm_size: u32) -> Self {
+        self.swarm_size = swarm_size;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Iterations without improvement before the swarm stops.
+    pub fn stagnation_limit(mut self, stagnation_limit: u8) -> Self {
+        self.stagnation_limit = stagnation_limit;
+        self
+    }
+
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    // repairs the position in place and returns its gain
+    fn evaluate(&self, bits: &mut Vec<bool>) -> i64 {
+        let mut genes = bits_to_genes(bits);
+        if operators::repair(&self.problem, &mut genes) {
+            *bits = genes_to_bits(&genes);
+        }
+        operators::summary(&self.problem, &genes).0
+    }
+
+    pub fn solve(&self) -> Result<Solution, SolverError> {
+        self.settings.validate()?;
+        if self.problem.data.is_empty() {
+            return Err(SolverError::EmptyProblem);
+        }
+        if self.swarm_size == 0 {
+            return Err(SolverError::InvalidPopulationSize);
+        }
+        info!("Running particle swarm for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
+
+        let dimensions = 2 * self.problem.data.len();
+        let max_velocity = self.settings.max_velocity;
+        let mut rng = utils::make_rng(self.seed);
+        let mut swarm: Vec<Particle> = (0..self.swarm_size).map(|_| {
+            let mut bits: Vec<bool> = (0..dimensions).map(|_| rng.gen_bool(0.5)).collect();
+            let velocity = (0..dimensions).map(|_| rng.gen_range(-max_velocity..=max_velocity)).collect();
+            let best = self.evaluate(&mut bits);
+            Particle { best_bits: bits.clone(), bits, velocity, best }
+        }).collect();
+        let leader = swarm.iter().max_by_key(|particle| particle.best).unwrap();
+        let (mut best_bits, mut best) = (leader.best_bits.clone(), leader.best);
+
+        let mut iterations: u32 = 0;
+        let mut stagnant: u32 = 0;
+        while stagnant <= self.stagnation_limit as u32 && !self.cancellation.is_cancelled() {
+            let mut improved = false;
+            for particle in swarm.iter_mut() {
+                for (d, global_best) in best_bits.iter().enumerate() {
+                    let (r1, r2) = (rng.gen::<f64>(), rng.gen::<f64>());
+                    let position = particle.bits[d] as u8 as f64;
+                    let velocity = self.settings.inertia * particle.velocity[d]
+                        + self.settings.cognitive * r1 * (particle.best_bits[d] as u8 as f64 - position)
+                        + self.settings.social * r2 * (*global_best as u8 as f64 - position);
+                    particle.velocity[d] = velocity.clamp(-max_velocity, max_velocity);
+                    particle.bits[d] = rng.gen::<f64>() < sigmoid(particle.velocity[d]);
+                }
+                let gain = self.evaluate(&mut particle.bits);
+                if gain > particle.best {
+                    particle.best = gain;
+                    particle.best_bits = particle.bits.clone();
+                }
+                if gain > best {
+                    best = gain;
+                    best_bits = particle.bits.clone();
+                    improved = true;
+                }
+            }
+            iterations += 1;
+            stagnant = if improved { 0 } else { stagnant + 1 };
+            info!("Particle swarm iteration: {} best: {}", iterations, best);
+        }
+
+        let choices = bits_to_genes(&best_bits);
+        debug!("Particle swarm stopped after {} iterations", iterations);
+        Ok(Solution::from_choices(&self.problem, choices, iterations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::make_thirty_groups;
+
+    #[test]
+    fn test_bits_encode_genes() {
+        let genes = vec![0, 1, 2, 3];
+        assert_eq!(genes_to_bits(&genes), vec![false, false, true, false, false, true, true, true]);
+        assert_eq!(bits_to_genes(&genes_to_bits(&genes)), genes);
+    }
+
+    #[test]
+    fn test_evaluate_repairs_position() {
+        let problem = make_thirty_groups();
+        let swarm = ParticleSwarm::new(problem.clone());
+        // every group takes its third item, far over the capacity
+        let mut bits = vec![true; 60];
+        let gain = swarm.evaluate(&mut bits);
+        let (expected_gain, cost) = operators::summary(&problem, &bits_to_genes(&bits));
+        assert_eq!(gain, expected_gain);
+        assert!(cost <= 300);
+        assert!(bits.iter().any(|bit| !bit));
+    }
+
+    #[test]
+    fn test_solve_rejects_empty_swarm() {
+        let result = ParticleSwarm::new(make_thirty_groups()).swarm_size(0).solve();
+        assert_eq!(result.unwrap_err(), SolverError::InvalidPopulationSize);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::error::SolverError;
+use crate::preprocessing::{PreprocessingResult, ProblemPreprocessor};
+use crate::solver::{Cancellation, Solver, SolverStatistics};
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use log::{debug, info};
+use std::sync::Arc;
+
+/// Core problem left once the groups fixed by the preprocessing are removed.
+///
+/// The UB-fix proves that every solution better than its own one takes the third item of the
+/// groups in `f_1`, and the reduced-cost fixing the item of its own `f_1` groups. These groups are
+/// removed and their cost is taken from the capacity. Groups whose items are all fixed to 0 by
+/// the reduced-cost fixing, or where no item fits in the remaining capacity, are then removed as
+/// empty. LP-dominated items (`f_0`) are kept, the dominance holds for the relaxation but not for
+/// the integer problem.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{DynamicProgrammingSolver, Item, Problem, ProblemPreprocessor, ProblemReduction};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+/// let reduction = ProblemReduction::new(problem, &preprocessing);
+/// let reduced = DynamicProgrammingSolver::new(reduction.reduced().clone(), usize::MAX).solve().unwrap();
+/// let solution = reduction.expand(&reduced);
+/// assert_eq!(solution.fitness(), 55);
+/// assert_eq!(solution.choices().len(), 2);
+/// ```
+pub struct ProblemReduction {
+    problem: Arc<Problem>,
+    reduced: Problem,
+    // original index of every group of the reduced problem
+    kept: Vec<usize>,
+    // choice of every original group, the kept groups are left at 0
+    fixed: Vec<usize>,
+    // UB-fix solution, better than any solution following the fixings when they are infeasible
+    fallback: Option<Vec<usize>>,
+    // whether the fixed groups fit in the capacity
+    feasible: bool,
+}
+
+impl ProblemReduction {
+    pub fn new(problem: impl Into<Arc<Problem>>, preprocessing_result: &PreprocessingResult) -> Self {
+        let shared: Arc<Problem> = problem.into();
+        let problem = shared.as_ref();
+        let m = problem.data.len();
+        let mut fixed = vec![0; m];
+        // the UB-fix relies on the dominance rules, which expect items ordered by cost
+        if ProblemReduction::is_ordered(problem) {
+            for (group, _) in &preprocessing_result.ub_fix_result.f_1 {
+                fixed[*group] = 3;
+            }
+        }
+        let reduced_cost_result = &preprocessing_result.reduced_cost_result;
+        for (group, item) in &reduced_cost_result.f_1 {
+            fixed[*group] = item + 1;
+        }
+        // groups whose items are all fixed to 0 stay empty
+        let mut excluded = vec![0; m];
+        for (group, _) in &reduced_cost_result.f_0 {
+            excluded[*group] += 1;
+        }
+        let fallback = ProblemReduction::fallback(problem, preprocessing_result);
+
+        let fixed_cost: i64 = fixed.iter().enumerate().filter(|(_, choice)| **choice != 0).map(|(i, choice)| problem.data[i][choice - 1].cost).sum();
+        let remaining_capacity = problem.capacity as i64 - fixed_cost;
+        let kept: Vec<usize> = if remaining_capacity < 0 {
+            debug!("Fixed groups exceed the capacity by {}, the UB-fix solution is optimal", -remaining_capacity);
+            vec![]
+        } else {
+            (0..m).filter(|i| fixed[*i] == 0 && excluded[*i] < problem.data[*i].len() && problem.data[*i].iter().any(|item| item.cost <= remaining_capacity)).collect()
+        };
+
+        let data = kept.iter().map(|i| problem.data[*i].clone()).collect();
+        let reduced = Problem::new(remaining_capacity.max(0) as u32, data);
+        info!("Reduction removed {} groups out of {}, remaining capacity: {}", m - kept.len(), m, reduced.capacity);
+        ProblemReduction { problem: shared, reduced, kept, fixed, fallback, feasible: remaining_capacity >= 0 }
+    }
+
+    pub(crate) fn is_ordered(problem: &Problem) -> bool {
+        problem.data.iter().all(|g| g.len() == 3 && g[0].cost < g[1].cost && g[1].cost < g[2].cost)
+    }
+
+    fn fallback(problem: &Problem, preprocessing_result: &PreprocessingResult) -> Option<Vec<usize>> {
+        let choices = preprocessing_result.ub_fix_result.choices();
+        if choices.len() != problem.data.len() {
+            return None;
+        }
+        let cost: i64 = choices.iter().enumerate().filter(|(_, c)| **c != 0).map(|(i, c)| problem.data[i][c - 1].cost).sum();
+        (cost <= problem.capacity as i64).then_some(choices)
+    }
+
+    /// Problem over the groups left free, with the capacity left by the fixed groups.
+    pub fn reduced(&self) -> &Problem {
+        &self.reduced
+    }
+
+    /// Number of groups removed from the original problem.
+    pub fn removed_groups(&self) -> usize {
+        self.problem.data.len() - self.kept.len()
+    }
+
+    /// Gain of the items taken by the fixed groups.
+    pub fn fixed_gain(&self) -> i64 {
+        if !self.feasible {
+            return 0;
+        }
+        self.fixed.iter().enumerate().filter(|(_, choice)| **choice != 0).map(|(i, choice)| self.problem.data[i][choice - 1].gain).sum()
+    }
+
+    /// Maps a solution of the reduced problem back to the original groups, the UB-fix solution
+    /// is returned instead when it is better.
+    pub fn expand(&self, reduced_solution: &Solution) -> Solution {
+        let mut choices = self.fixed.clone();
+        if self.feasible {
+            for (k, choice) in reduced_solution.choices.iter().enumerate() {
+                choices[self.kept[k]] = *choice;
+            }
+        } else {
+            choices.fill(0);
+        }
+        let solution = Solution::from_choices(&self.problem, choices, reduced_solution.generations);
+        match &self.fallback {
+            Some(fallback) => {
+                let fallback = Solution::from_choices(&self.problem, fallback.clone(), reduced_solution.generations);
+                if fallback.fitness > solution.fitness { fallback } else { solution }
+            }
+            None => solution,
+        }
+    }
+
+}
+
+/// Runs a solver on the problem reduced by the preprocessing and maps its solution back.
+///
+/// The upper bound of the inner solver, or the Lagrangian bound of the reduced problem when it is
+/// lower, is shifted by the gain of the fixed groups. An optimal solution of the reduced problem
+/// is optimal for the original one.
+pub struct ReducedSolver {
+    inner: Box<dyn Solver>,
+}
+
+impl ReducedSolver {
+    pub fn new(inner: Box<dyn Solver>) -> Self {
+        ReducedSolver { inner }
+    }
+}
+
+impl Solver for ReducedSolver {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let preprocessing_result = ProblemPreprocessor::new(&problem).process_problem();
+        let reduction = ProblemReduction::new(problem, &preprocessing_result);
+
+        let (reduced_solution, statistics) = if reduction.reduced.data.is_empty() {
+            let empty = Solution::make_solution(vec![], vec![], 0, 0, 0);
+            (empty, SolverStatistics { proven_optimal: true, upper_bound: Some(0.0), ..SolverStatistics::default() })
+        } else {
+            self.inner.solve(reduction.reduced.clone(), cancellation)?
+        };
+
+        let solution = reduction.expand(&reduced_solution);
+        let v_best = preprocessing_result.ub_fix_result.v_best;
+        // the Lagrangian bound of the reduced problem holds for every solution following the fixings
+        let lagrangian_bound = ProblemPreprocessor::new(&reduction.reduced).lagrangian_relaxation().bound();
+        let reduced_bound = statistics.upper_bound.map_or(lagrangian_bound, |bound| bound.min(lagrangian_bound));
+        let upper_bound = Some((reduced_bound + reduction.fixed_gain() as f64).max(v_best));
+        let statistics = SolverStatistics {
+            upper_bound,
+            gap: upper_bound.map(|bound| if bound > 0.0 { ((bound - solution.fitness as f64) / bound).max(0.0) } else { 0.0 }),
+            removed_groups: Some(reduction.removed_groups()),
+            v_low: None,
+            v_best: None,
+            ..statistics
+        }.preprocessing(&preprocessing_result);
+        Ok((solution, statistics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::SolverBuilder;
+    use crate::dp::DynamicProgrammingSolver;
+    use crate::solver::SolverRegistry;
+    use crate::structure::{make_item, make_problem};
+    use crate::utils;
+    use rand::Rng;
+
+    fn make_random_problem(seed: u64, m: usize) -> Problem {
+        let mut rng = utils::make_rng(seed);
+        let data = (0..m).map(|_| {
+            let (c1, c2) = (rng.gen_range(10..60), rng.gen_range(10..60));
+            let (g1, g2) = (rng.gen_range(5..80), rng.gen_range(5..80));
+            let (c1, c2) = if c1 == c2 { (c1, c2 + 1) } else { (c1.min(c2), c1.max(c2)) };
+            let c3 = c2 + rng.gen_range(1..c1);
+            vec![make_item(g1, c1), make_item(g2, c2), make_item(g1 + g2, c3)]
+        }).collect::<Vec<_>>();
+        let total: i64 = data.iter().map(|g| g[2].cost).sum();
+        make_problem((total / 2) as u32, data, m as i32)
+    }
+
+    #[test]
+    fn test_expanded_solution_matches_dp() {
+        for seed in 0..20 {
+            let problem = make_random_problem(seed, 12);
+            let optimum = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap().fitness;
+
+            let preprocessing = ProblemPreprocessor::new(&problem).process_problem();
+            let reduction = ProblemReduction::new(problem.clone(), &preprocessing);
+            assert_eq!(reduction.removed_groups(), problem.data.len() - reduction.reduced().data.len());
+            let reduced = DynamicProgrammingSolver::new(reduction.reduced().clone(), usize::MAX).solve().unwrap();
+            let solution = reduction.expand(&reduced);
+
+            assert_eq!(solution.fitness, optimum, "seed {}", seed);
+            assert_eq!(solution.choices.len(), problem.data.len());
+            assert!(solution.cost <= problem.capacity as i64);
+        }
+    }
+
+    #[test]
+    fn test_reduced_solver_reports_removed_groups() {
+        let problem = make_random_problem(3, 12);
+        let optimum = DynamicProgrammingSolver::new(problem.clone(), usize::MAX).solve().unwrap().fitness;
+        let inner = SolverRegistry::default().create("bnb", &SolverBuilder::new()).unwrap();
+        let (solution, statistics) = ReducedSolver::new(inner).solve(problem, &Cancellation::new()).unwrap();
+        assert_eq!(solution.fitness, optimum);
+        assert!(statistics.removed_groups().is_some());
+        assert!(statistics.upper_bound().unwrap() >= optimum as f64);
+    }
+}
@@ -1,37 +1,51 @@
+use crate::solver::SolverStatistics;
+use crate::structure::seeding_mix::{SeedingCounts, SeedingStrategy};
 use crate::structure::solution::Solution;
 use csv::Writer;
 use std::env;
 use std::fs::OpenOptions;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-pub(crate) struct Metric {
+pub struct Metric {
+    algorithm: String,
     architecture: String,
     best_fitness: i64,
     elapsed: Duration,
     enhanced: bool,
+    epsilon: Option<f64>,
     file_name: String,
+    gap: Option<f64>,
     generation: u32,
     instance: String,
+    nodes: Option<u64>,
     no_upgrade_limit: u8,
     os_description: String,
     population_size: u32,
     seed: u64,
+    seeding: SeedingCounts,
     starting_time: SystemTime,
+    winner: Option<String>,
+    upper_bound: Option<f64>,
+    v_low: Option<f64>,
+    v_best: Option<f64>,
+    proven_optimal: bool,
+    removed_groups: Option<usize>,
+    domain_constrained: bool,
 }
 
-pub(crate) trait Exporter {
+pub trait Exporter {
     fn export(&self, row: Metric);
 }
 
 
 #[derive(Clone)]
-pub(crate) struct CSV {
-    pub(crate) path: String,
+pub struct Csv {
+    pub path: String,
 }
 
-impl CSV {
+impl Csv {
     fn row_to_record(&self, row: Metric) -> Vec<String> {
-        vec![
+        let mut record = vec![
             row.instance,
             format!("{}", row.starting_time.duration_since(UNIX_EPOCH).unwrap().as_secs()),
             row.file_name,
@@ -44,18 +58,34 @@ impl CSV {
             row.os_description,
             row.architecture,
             row.enhanced.to_string(),
-        ]
+        ];
+        // the rows are appended without a header, a column added later goes at the end of the row
+        record.extend(SeedingStrategy::ALL.iter()
+            .filter(|s| **s != SeedingStrategy::Lagrangian)
+            .map(|s| row.seeding.get(*s).to_string()));
+        record.push(row.algorithm);
+        record.push(row.nodes.map_or(String::new(), |nodes| nodes.to_string()));
+        record.push(row.gap.map_or(String::new(), |gap| gap.to_string()));
+        record.push(row.epsilon.map_or(String::new(), |epsilon| epsilon.to_string()));
+        record.push(row.winner.unwrap_or_default());
+        record.push(row.upper_bound.map_or(String::new(), |bound| bound.to_string()));
+        record.push(row.v_low.map_or(String::new(), |v_low| v_low.to_string()));
+        record.push(row.v_best.map_or(String::new(), |v_best| v_best.to_string()));
+        record.push(row.proven_optimal.to_string());
+        record.push(row.removed_groups.map_or(String::new(), |removed| removed.to_string()));
+        record.push(row.domain_constrained.to_string());
+        record.push(row.seeding.get(SeedingStrategy::Lagrangian).to_string());
+        record
     }
 }
 
 
-impl Exporter for CSV {
+impl Exporter for Csv {
     fn export(&self, row: Metric) {
         let file = OpenOptions::new()
-            .write(true)
             .create(true)
             .append(true)
-            .open(self.path.to_string())
+            .open(&self.path)
             .unwrap();
         let mut wtr = Writer::from_writer(file);
         wtr.write_record(self.row_to_record(row)).unwrap();
@@ -64,35 +94,184 @@ impl Exporter for CSV {
 }
 
 
-pub(crate) struct Report;
+/// Collects the metrics of a run before exporting them as a single row.
+pub struct Report {
+    metric: Metric,
+}
 
 impl Report {
-    pub(crate) fn generate<T: Exporter>(g: T,
-                                        instance: String,
-                                        starting_time: SystemTime,
-                                        file_name: String,
-                                        seed: u64,
-                                        no_upgrade_limit: u8,
-                                        population_size: u32,
-                                        solution: &Solution,
-                                        duration: Duration,
-                                        enhanced: bool,
-    ) {
-        let metric = Metric {
-            architecture: env::consts::ARCH.to_string(),
-            best_fitness: solution.fitness,
-            elapsed: duration,
-            enhanced,
-            file_name,
-            generation: solution.generations,
-            instance,
-            no_upgrade_limit,
-            os_description: env::consts::OS.to_string(),
-            population_size,
-            seed,
-            starting_time,
-        };
-
-        g.export(metric);
-    }
-}
\ No newline at end of file
+    pub fn new(instance: String, file_name: String, starting_time: SystemTime) -> Self {
+        Report {
+            metric: Metric {
+                algorithm: "ga".to_string(),
+                architecture: env::consts::ARCH.to_string(),
+                best_fitness: 0,
+                elapsed: Duration::ZERO,
+                enhanced: false,
+                file_name,
+                gap: None,
+                epsilon: None,
+                winner: None,
+                upper_bound: None,
+                v_low: None,
+                v_best: None,
+                proven_optimal: false,
+                removed_groups: None,
+                domain_constrained: false,
+                generation: 0,
+                instance,
+                nodes: None,
+                no_upgrade_limit: 0,
+                os_description: env::consts::OS.to_string(),
+                population_size: 0,
+                seed: 0,
+                seeding: SeedingCounts::default(),
+                starting_time,
+            },
+        }
+    }
+
+    pub fn algorithm(mut self, algorithm: &str) -> Self {
+        self.metric.algorithm = algorithm.to_string();
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.metric.seed = seed;
+        self
+    }
+
+    pub fn no_upgrade_limit(mut self, no_upgrade_limit: u8) -> Self {
+        self.metric.no_upgrade_limit = no_upgrade_limit;
+        self
+    }
+
+    pub fn population_size(mut self, population_size: u32) -> Self {
+        self.metric.population_size = population_size;
+        self
+    }
+
+    pub fn enhanced(mut self, enhanced: bool) -> Self {
+        self.metric.enhanced = enhanced;
+        self
+    }
+
+    pub fn seeding(mut self, seeding: SeedingCounts) -> Self {
+        self.metric.seeding = seeding;
+        self
+    }
+
+    pub fn nodes(mut self, nodes: u64) -> Self {
+        self.metric.nodes = Some(nodes);
+        self
+    }
+
+    /// Upper bound on the optimum, the lowest of the bounds given is kept. The gap is then
+    /// computed from it when the solution is set.
+    pub fn upper_bound(mut self, upper_bound: f64) -> Self {
+        self.metric.upper_bound = Some(self.metric.upper_bound.map_or(upper_bound, |bound| bound.min(upper_bound)));
+        self
+    }
+
+    /// Relative gap between the best value and a proven upper bound.
+    pub fn gap(mut self, gap: f64) -> Self {
+        self.metric.gap = Some(gap);
+        self
+    }
+
+    /// Approximation factor, the best value is at least (1 - epsilon) of the optimum.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.metric.epsilon = Some(epsilon);
+        self
+    }
+
+    /// Fills the columns computed by the solver.
+    pub fn statistics(mut self, statistics: &SolverStatistics) -> Self {
+        if let Some(population_size) = statistics.population_size {
+            self.metric.population_size = population_size;
+        }
+        if let Some(no_upgrade_limit) = statistics.no_upgrade_limit {
+            self.metric.no_upgrade_limit = no_upgrade_limit;
+        }
+        if let Some(enhanced) = statistics.enhanced {
+            self.metric.enhanced = enhanced;
+        }
+        if let Some(seeding) = &statistics.seeding {
+            self.metric.seeding = seeding.clone();
+        }
+        self.metric.nodes = statistics.nodes.or(self.metric.nodes);
+        self.metric.gap = statistics.gap.or(self.metric.gap);
+        self.metric.epsilon = statistics.epsilon.or(self.metric.epsilon);
+        self.metric.winner = statistics.winner.clone().or(self.metric.winner.take());
+        self.metric.v_low = statistics.v_low.or(self.metric.v_low);
+        self.metric.v_best = statistics.v_best.or(self.metric.v_best);
+        self.metric.proven_optimal |= statistics.proven_optimal;
+        self.metric.removed_groups = statistics.removed_groups.or(self.metric.removed_groups);
+        if let Some(domain_constrained) = statistics.domain_constrained {
+            self.metric.domain_constrained = domain_constrained;
+        }
+        match statistics.upper_bound {
+            Some(upper_bound) => self.upper_bound(upper_bound),
+            None => self,
+        }
+    }
+
+    pub fn solution(mut self, solution: &Solution, elapsed: Duration) -> Self {
+        self.metric.best_fitness = solution.fitness;
+        if let Some(upper_bound) = self.metric.upper_bound {
+            self.metric.proven_optimal |= solution.fitness as f64 >= upper_bound.floor();
+            self.metric.gap = Some(if upper_bound > 0.0 { ((upper_bound - solution.fitness as f64) / upper_bound).max(0.0) } else { 0.0 });
+        }
+        self.metric.generation = solution.generations;
+        self.metric.elapsed = elapsed;
+        self
+    }
+
+    pub fn export<T: Exporter>(self, g: T) {
+        g.export(self.metric);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::solution::Solution;
+
+    #[test]
+    fn test_gap_uses_lowest_upper_bound() {
+        let statistics = SolverStatistics { upper_bound: Some(95.0), v_low: Some(70.0), v_best: Some(76.0), ..SolverStatistics::default() };
+        let solution = Solution::make_solution(vec![], vec![0, 1], 76, 10, 3);
+        let report = Report::new("id".to_string(), "file".to_string(), UNIX_EPOCH)
+            .upper_bound(100.0)
+            .statistics(&statistics)
+            .solution(&solution, Duration::ZERO);
+
+        let metric = &report.metric;
+        assert_eq!(metric.upper_bound, Some(95.0));
+        assert_eq!(metric.gap, Some((95.0 - 76.0) / 95.0));
+        assert_eq!((metric.v_low, metric.v_best), (Some(70.0), Some(76.0)));
+        assert!(!metric.proven_optimal);
+    }
+
+    #[test]
+    fn test_lagrangian_share_ends_the_row() {
+        let report = Report::new("id".to_string(), "file".to_string(), UNIX_EPOCH)
+            .seeding(SeedingCounts::only(SeedingStrategy::Lagrangian, 7));
+        let record = Csv { path: String::new() }.row_to_record(report.metric);
+
+        // the shares of the older strategies keep their columns, right after the enhanced flag
+        assert_eq!(record[11..21], ["false", "0", "0", "0", "0", "0", "0", "0", "0", "0"]);
+        assert_eq!(record[21], "ga");
+        assert_eq!(record.last().unwrap(), "7");
+    }
+
+    #[test]
+    fn test_solution_reaching_bound_is_proven_optimal() {
+        let solution = Solution::make_solution(vec![], vec![0, 1], 95, 10, 3);
+        let report = Report::new("id".to_string(), "file".to_string(), UNIX_EPOCH)
+            .upper_bound(95.8)
+            .solution(&solution, Duration::ZERO);
+        assert!(report.metric.proven_optimal);
+        assert_eq!(report.metric.gap, Some((95.8 - 95.0) / 95.8));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::annealing::SimulatedAnnealing;
+use crate::bnb::BranchAndBoundSolver;
+use crate::brkga::Brkga;
+use crate::builder::SolverBuilder;
+use crate::de::DifferentialEvolution;
+use crate::dp::DynamicProgrammingSolver;
+use crate::error::SolverError;
+use crate::fptas::FptasSolver;
+use crate::generator::{GreedyChromosomeGenerator, InitializationStrategy};
+use crate::genetic::GeneticAlgorithm;
+use crate::portfolio::Portfolio;
+use crate::preprocessing::ProblemPreprocessor;
+use crate::pso::ParticleSwarm;
+use crate::solver::{Cancellation, Solver, SolverRegistry, SolverStatistics};
+use crate::structure::problem::Problem;
+use crate::structure::seeding_mix::{SeedingCounts, SeedingStrategy};
+use crate::structure::solution::Solution;
+use crate::tabu::TabuSearch;
+use std::sync::Arc;
+
+pub(crate) fn register(registry: &mut SolverRegistry) {
+    registry.register("ga", |builder| Box::new(Genetic(builder.clone())));
+    registry.register("dp", |builder| Box::new(DynamicProgramming(builder.clone())));
+    registry.register("bnb", |builder| Box::new(BranchAndBound(builder.clone())));
+    registry.register("fptas", |builder| Box::new(Fptas(builder.clone())));
+    registry.register("greedy", |_| Box::new(Greedy));
+    registry.register("sa", |builder| Box::new(Annealing(builder.clone())));
+    registry.register("tabu", |builder| Box::new(Tabu(builder.clone())));
+    registry.register("pso", |builder| Box::new(Swarm(builder.clone())));
+    registry.register("de", |builder| Box::new(Differential(builder.clone())));
+    registry.register("brkga", |builder| Box::new(RandomKeys(builder.clone())));
+    registry.register("portfolio", |builder| Box::new(GeneticPortfolio(builder.clone())));
+}
+
+struct Genetic(SolverBuilder);
+
+impl Solver for Genetic {
+    fn name(&self) -> &'static str {
+        "ga"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let mut executor = self.0.clone().problem(problem).build()?;
+        executor.set_cancellation(cancellation.clone());
+        let solution = executor.run();
+        let configuration = executor.configuration();
+        let statistics = SolverStatistics {
+            upper_bound: Some(executor.upper_bound()),
+            proven_optimal: executor.proven_optimal(),
+            population_size: Some(configuration.get_population_size()),
+            no_upgrade_limit: Some(configuration.get_no_upgrade_limit()),
+            enhanced: Some(configuration.is_enhanced_enabled()),
+            seeding: Some(executor.seeding_counts()),
+            domain_constrained: Some(executor.domain_constrained()),
+            ..SolverStatistics::default()
+        }.preprocessing(executor.preprocessing_result());
+        Ok((solution, statistics))
+    }
+}
+
+struct GeneticPortfolio(SolverBuilder);
+
+impl Solver for GeneticPortfolio {
+    fn name(&self) -> &'static str {
+        "portfolio"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let (solution, statistics) = Portfolio::new(problem)
+            .varied_members(&self.0, self.0.portfolio_size)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        let statistics = SolverStatistics {
+            no_upgrade_limit: Some(self.0.configuration.no_upgrade_limit),
+            winner: Some(statistics.winner_label().to_string()),
+            ..SolverStatistics::default()
+        };
+        Ok((solution, statistics))
+    }
+}
+
+struct DynamicProgramming(SolverBuilder);
+
+impl Solver for DynamicProgramming {
+    fn name(&self) -> &'static str {
+        "dp"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let solution = DynamicProgrammingSolver::new(problem, self.0.memory_budget)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        let statistics = SolverStatistics {
+            upper_bound: Some(solution.fitness() as f64),
+            gap: Some(0.0),
+            proven_optimal: true,
+            ..SolverStatistics::default()
+        };
+        Ok((solution, statistics))
+    }
+}
+
+struct BranchAndBound(SolverBuilder);
+
+impl Solver for BranchAndBound {
+    fn name(&self) -> &'static str {
+        "bnb"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let preprocessing_result = ProblemPreprocessor::new(&problem).process_problem();
+        // shared with the genetic algorithm giving the incumbent
+        let problem = Arc::new(problem);
+        let mut solver = BranchAndBoundSolver::new(Arc::clone(&problem))
+            .preprocessing_result(preprocessing_result.clone())
+            .cancellation(cancellation.clone());
+        if self.0.ga_incumbent {
+            let mut executor = self.0.clone().problem(problem).preprocessing_result(preprocessing_result.clone()).build()?;
+            executor.set_cancellation(cancellation.clone());
+            solver = solver.incumbent(executor.run().choices().to_vec());
+        }
+        let (solution, statistics) = solver.solve()?;
+        let statistics = SolverStatistics {
+            nodes: Some(statistics.nodes()),
+            upper_bound: Some(statistics.upper_bound()),
+            gap: Some(statistics.gap()),
+            proven_optimal: statistics.proven_optimal(),
+            ..SolverStatistics::default()
+        }.preprocessing(&preprocessing_result);
+        Ok((solution, statistics))
+    }
+}
+
+struct Fptas(SolverBuilder);
+
+impl Solver for Fptas {
+    fn name(&self) -> &'static str {
+        "fptas"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let (solution, statistics) = FptasSolver::new(problem, self.0.epsilon)
+            .memory_budget(self.0.memory_budget)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        let statistics = SolverStatistics {
+            upper_bound: Some(statistics.upper_bound()),
+            gap: Some(statistics.gap()),
+            epsilon: Some(statistics.epsilon()),
+            ..SolverStatistics::default()
+        };
+        Ok((solution, statistics))
+    }
+}
+
+struct Annealing(SolverBuilder);
+
+impl Solver for Annealing {
+    fn name(&self) -> &'static str {
+        "sa"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let configuration = &self.0.configuration;
+        let (solution, _) = SimulatedAnnealing::new(problem)
+            .schedule(self.0.annealing_schedule.clone())
+            .seed(configuration.seed)
+            .stagnation_limit(configuration.no_upgrade_limit)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        Ok((solution, population_statistics(configuration.no_upgrade_limit, 1)))
+    }
+}
+
+struct Tabu(SolverBuilder);
+
+impl Solver for Tabu {
+    fn name(&self) -> &'static str {
+        "tabu"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let configuration = &self.0.configuration;
+        let (solution, _) = TabuSearch::new(problem)
+            .settings(self.0.tabu_settings.clone())
+            .seed(configuration.seed)
+            .stagnation_limit(configuration.no_upgrade_limit)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        Ok((solution, population_statistics(configuration.no_upgrade_limit, 1)))
+    }
+}
+
+struct Swarm(SolverBuilder);
+
+impl Solver for Swarm {
+    fn name(&self) -> &'static str {
+        "pso"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let configuration = &self.0.configuration;
+        let swarm_size = self.0.population_size.resolve(problem.size);
+        let solution = ParticleSwarm::new(problem)
+            .settings(self.0.pso_settings.clone())
+            .swarm_size(swarm_size)
+            .seed(configuration.seed)
+            .stagnation_limit(configuration.no_upgrade_limit)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        Ok((solution, population_statistics(configuration.no_upgrade_limit, swarm_size)))
+    }
+}
+
+struct Differential(SolverBuilder);
+
+impl Solver for Differential {
+    fn name(&self) -> &'static str {
+        "de"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let configuration = &self.0.configuration;
+        let population_size = self.0.population_size.resolve(problem.size);
+        let solution = DifferentialEvolution::new(problem)
+            .settings(self.0.de_settings.clone())
+            .population_size(population_size)
+            .seed(configuration.seed)
+            .stagnation_limit(configuration.no_upgrade_limit)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        Ok((solution, population_statistics(configuration.no_upgrade_limit, population_size)))
+    }
+}
+
+struct RandomKeys(SolverBuilder);
+
+impl Solver for RandomKeys {
+    fn name(&self) -> &'static str {
+        "brkga"
+    }
+
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let configuration = &self.0.configuration;
+        let population_size = self.0.population_size.resolve(problem.size);
+        let solution = Brkga::new(problem)
+            .settings(self.0.brkga_settings.clone())
+            .population_size(population_size)
+            .seed(configuration.seed)
+            .stagnation_limit(configuration.no_upgrade_limit)
+            .cancellation(cancellation.clone())
+            .solve()?;
+        Ok((solution, population_statistics(configuration.no_upgrade_limit, population_size)))
+    }
+}
+
+// metrics of a randomly initialized metaheuristic, in the columns of the genetic algorithm
+fn population_statistics(no_upgrade_limit: u8, population_size: u32) -> SolverStatistics {
+    SolverStatistics {
+        population_size: Some(population_size),
+        no_upgrade_limit: Some(no_upgrade_limit),
+        enhanced: Some(false),
+        seeding: Some(SeedingCounts::only(SeedingStrategy::Random, population_size)),
+        ..SolverStatistics::default()
+    }
+}
+
+// ratio greedy used to seed the genetic algorithm, as a baseline
+struct Greedy;
+
+impl Solver for Greedy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn solve(&mut self, problem: Problem, _: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError> {
+        let choices = GreedyChromosomeGenerator::new(&problem).generate_chromosome().choices();
+        Ok((Solution::from_choices(&problem, choices, 0), SolverStatistics::default()))
+    }
+}
@@ -0,0 +1,258 @@
+use crate::builder::SolverBuilder;
+use crate::error::SolverError;
+use crate::preprocessing::PreprocessingResult;
+use crate::structure::problem::Problem;
+use crate::structure::seeding_mix::SeedingCounts;
+use crate::structure::solution::Solution;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod algorithms;
+
+/// Shared flag telling a running solver to stop, optionally triggered by a deadline.
+///
+/// Clones share the flag, so a solver can be cancelled from another thread. Solvers able to
+/// return their best solution so far do so, the others fail with [`SolverError::Cancelled`].
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Cancellation::default()
+    }
+
+    /// Triggers the cancellation once `time_limit` has elapsed from now.
+    pub fn time_limit(mut self, time_limit: Duration) -> Self {
+        self.deadline = Some(Instant::now() + time_limit);
+        self
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Figures reported by a solver next to its solution, absent when the solver does not compute them.
+#[derive(Debug, Clone, Default)]
+pub struct SolverStatistics {
+    pub(crate) nodes: Option<u64>,
+    pub(crate) upper_bound: Option<f64>,
+    pub(crate) gap: Option<f64>,
+    pub(crate) proven_optimal: bool,
+    pub(crate) epsilon: Option<f64>,
+    pub(crate) population_size: Option<u32>,
+    pub(crate) no_upgrade_limit: Option<u8>,
+    pub(crate) enhanced: Option<bool>,
+    pub(crate) seeding: Option<SeedingCounts>,
+    pub(crate) winner: Option<String>,
+    pub(crate) v_low: Option<f64>,
+    pub(crate) v_best: Option<f64>,
+    pub(crate) removed_groups: Option<usize>,
+    pub(crate) domain_constrained: Option<bool>,
+}
+
+impl SolverStatistics {
+    pub fn nodes(&self) -> Option<u64> {
+        self.nodes
+    }
+
+    pub fn upper_bound(&self) -> Option<f64> {
+        self.upper_bound
+    }
+
+    pub fn gap(&self) -> Option<f64> {
+        self.gap
+    }
+
+    pub fn proven_optimal(&self) -> bool {
+        self.proven_optimal
+    }
+
+    pub fn epsilon(&self) -> Option<f64> {
+        self.epsilon
+    }
+
+    pub fn population_size(&self) -> Option<u32> {
+        self.population_size
+    }
+
+    pub fn no_upgrade_limit(&self) -> Option<u8> {
+        self.no_upgrade_limit
+    }
+
+    pub fn enhanced(&self) -> Option<bool> {
+        self.enhanced
+    }
+
+    pub fn seeding(&self) -> Option<&SeedingCounts> {
+        self.seeding.as_ref()
+    }
+
+    /// Lower bound of the LP relaxation, when the preprocessing has been run.
+    pub fn v_low(&self) -> Option<f64> {
+        self.v_low
+    }
+
+    /// Value of the UB-fix solution, when the preprocessing has been run.
+    pub fn v_best(&self) -> Option<f64> {
+        self.v_best
+    }
+
+    /// Groups removed by the problem reduction, when the solver ran on the reduced problem.
+    pub fn removed_groups(&self) -> Option<usize> {
+        self.removed_groups
+    }
+
+    /// Whether the genetic operators were restricted by the preprocessing.
+    pub fn domain_constrained(&self) -> Option<bool> {
+        self.domain_constrained
+    }
+
+    /// Configuration that found the best solution, when several ran.
+    pub fn winner(&self) -> Option<&str> {
+        self.winner.as_deref()
+    }
+}
+
+impl SolverStatistics {
+    pub(crate) fn preprocessing(mut self, preprocessing_result: &PreprocessingResult) -> Self {
+        if !preprocessing_result.relaxation_result.relaxed.is_empty() {
+            self.v_low = Some(preprocessing_result.relaxation_result.v_low);
+            self.v_best = Some(preprocessing_result.ub_fix_result.v_best);
+        }
+        self
+    }
+}
+
+/// Algorithm solving a problem into a [`Solution`].
+///
+/// ```
+/// use discounted_zeroone_knapsack::{Cancellation, Item, Problem, SolverBuilder, SolverRegistry};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let registry = SolverRegistry::default();
+/// let mut solver = registry.create("bnb", &SolverBuilder::new()).unwrap();
+/// let (solution, statistics) = solver.solve(problem, &Cancellation::new()).unwrap();
+/// assert_eq!(solver.name(), "bnb");
+/// assert_eq!(solution.fitness(), 55);
+/// assert!(statistics.proven_optimal());
+/// ```
+pub trait Solver {
+    fn name(&self) -> &'static str;
+    fn solve(&mut self, problem: Problem, cancellation: &Cancellation) -> Result<(Solution, SolverStatistics), SolverError>;
+}
+
+/// Creates a solver from the shared settings of a [`SolverBuilder`], the problem set on it is ignored.
+pub type SolverFactory = fn(&SolverBuilder) -> Box<dyn Solver>;
+
+/// Solvers available by name.
+pub struct SolverRegistry {
+    factories: BTreeMap<&'static str, SolverFactory>,
+}
+
+impl SolverRegistry {
+    /// Registry without any solver.
+    pub fn new() -> Self {
+        SolverRegistry { factories: BTreeMap::new() }
+    }
+
+    /// Registers `factory` under `name`, replacing the solver previously registered under it.
+    pub fn register(&mut self, name: &'static str, factory: SolverFactory) {
+        self.factories.insert(name, factory);
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.factories.keys().copied().collect()
+    }
+
+    pub fn create(&self, name: &str, builder: &SolverBuilder) -> Result<Box<dyn Solver>, SolverError> {
+        match self.factories.get(name) {
+            Some(factory) => Ok(factory(builder)),
+            None => Err(SolverError::UnknownAlgorithm(name.to_string())),
+        }
+    }
+}
+
+impl Default for SolverRegistry {
+    /// Registry of the solvers of the crate: ga, portfolio, brkga, sa, tabu, pso, de, dp, bnb, fptas and greedy.
+    fn default() -> Self {
+        let mut registry = SolverRegistry::new();
+        algorithms::register(&mut registry);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    fn make_test_problem() -> Problem {
+        make_problem(30, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+            vec![make_item(8, 3), make_item(12, 6), make_item(19, 8)],
+        ], 3)
+    }
+
+    #[test]
+    fn test_default_solvers_return_feasible_solutions() {
+        let registry = SolverRegistry::default();
+        let builder = SolverBuilder::new().population_size(20).seed(3);
+        assert_eq!(registry.names(), vec!["bnb", "brkga", "de", "dp", "fptas", "ga", "greedy", "portfolio", "pso", "sa", "tabu"]);
+        for name in registry.names() {
+            if name == "portfolio" {
+                continue;
+            }
+            let mut solver = registry.create(name, &builder).unwrap();
+            let (solution, _) = solver.solve(make_test_problem(), &Cancellation::new()).unwrap();
+            assert_eq!(solver.name(), name);
+            assert!(solution.cost() <= 30);
+            assert!(solution.fitness() > 0);
+        }
+    }
+
+    #[test]
+    fn test_create_unknown_solver() {
+        let result = SolverRegistry::default().create("unknown", &SolverBuilder::new());
+        assert!(matches!(result, Err(SolverError::UnknownAlgorithm(name)) if name == "unknown"));
+    }
+
+    #[test]
+    fn test_register_replaces_solver() {
+        let mut registry = SolverRegistry::new();
+        registry.register("exact", |builder| SolverRegistry::default().create("dp", builder).unwrap());
+        let mut solver = registry.create("exact", &SolverBuilder::new()).unwrap();
+        assert_eq!(solver.solve(make_test_problem(), &Cancellation::new()).unwrap().0.fitness(), 75);
+    }
+
+    #[test]
+    fn test_cancelled_solvers() {
+        let registry = SolverRegistry::default();
+        let cancellation = Cancellation::new();
+        cancellation.cancel();
+
+        let mut genetic = registry.create("ga", &SolverBuilder::new().population_size(20)).unwrap();
+        let (solution, _) = genetic.solve(make_test_problem(), &cancellation).unwrap();
+        assert_eq!(solution.generations(), 0);
+
+        let mut exact = registry.create("dp", &SolverBuilder::new()).unwrap();
+        assert_eq!(exact.solve(make_test_problem(), &cancellation).unwrap_err(), SolverError::Cancelled);
+    }
+}
@@ -1,23 +1,33 @@
 use crate::structure::item::Item;
 use crate::structure::problem::Problem;
 
-pub(crate) mod problem;
-pub(crate) mod item;
-pub(crate) mod solution;
-pub(crate) mod chromosome;
-pub(crate) mod configuration;
-pub(crate) mod item_preprocessing;
-pub(crate) mod relaxation_result;
-pub(crate) mod fix_result;
+pub mod problem;
+pub mod item;
+pub mod solution;
+pub mod chromosome;
+pub mod configuration;
+pub mod constraint_handling;
+pub mod item_preprocessing;
+pub mod relaxation_result;
+pub mod fix_result;
+pub mod lagrangian_result;
+pub mod reduced_cost_result;
+pub mod seeding_mix;
 
 pub fn make_problem(capacity: u32,
                     data: Vec<Vec<Item>>,
                     size: i32, ) -> Problem {
-    Problem {
-        capacity,
-        data,
-        size,
-    }
+    Problem::with_size(capacity, data, size)
+}
+
+/// Thirty groups of varied efficiency under a capacity of 300, for the tests of the solvers.
+#[cfg(test)]
+pub(crate) fn make_thirty_groups() -> Problem {
+    let data: Vec<_> = (0..30).map(|i| {
+        let (first, second) = (10 + (i * 7) % 23, 12 + (i * 11) % 19);
+        vec![make_item(first + 3, first), make_item(second + 5, second), make_item(first + second + 8, first + second - 2)]
+    }).collect();
+    make_problem(300, data, 30)
 }
 
 pub fn make_item(gain: i64,
@@ -1,25 +1,114 @@
+use crate::structure::problem::Problem;
+
+/// Option held by a gene, 0 being the empty option, stored as `u8` in the chromosomes and as
+/// `usize` by the single-solution metaheuristics.
+pub(crate) trait Gene: Copy + PartialEq {
+    fn option(self) -> usize;
+    fn from_option(option: usize) -> Self;
+}
+
+impl Gene for u8 {
+    fn option(self) -> usize {
+        self as usize
+    }
+
+    fn from_option(option: usize) -> Self {
+        option as u8
+    }
+}
+
+impl Gene for usize {
+    fn option(self) -> usize {
+        self
+    }
+
+    fn from_option(option: usize) -> Self {
+        option
+    }
+}
+
+/// One choice per group.
+///
+/// The genetic algorithm caches the total gain and cost of the choices, computed when the
+/// chromosome enters the population and kept up to date by its operators.
 #[derive(Debug, Clone)]
 pub struct Chromosome {
-    pub(crate) genes: Vec<usize>,
+    pub(crate) genes: Vec<u8>,
     pub(crate) fitness: i64,
     pub(crate) age: i32,
+    pub(crate) gain: i64,
+    pub(crate) cost: i64,
 }
 
 impl Chromosome {
-    pub fn init_chromosome(genes: Vec<usize>) -> Self {
+    pub fn init_chromosome(genes: Vec<u8>) -> Self {
         Chromosome {
             genes,
             fitness: 0,
             age: 0,
+            gain: 0,
+            cost: 0,
         }
     }
 
-    pub fn set_fitness(self: &mut Self, fitness: i64) {
+    pub fn genes(&self) -> &[u8] {
+        &self.genes
+    }
+
+    pub fn fitness(&self) -> i64 {
+        self.fitness
+    }
+
+    pub fn age(&self) -> i32 {
+        self.age
+    }
+
+    /// Genes widened to the choices of a [`Solution`](crate::structure::solution::Solution).
+    pub fn choices(&self) -> Vec<usize> {
+        self.genes.iter().map(|gene| *gene as usize).collect()
+    }
+
+    pub fn set_fitness(&mut self, fitness: i64) {
         self.fitness = fitness;
     }
 
-    pub fn increase_age(self: &mut Self) {
-        self.age = self.age + 1;
+    pub fn increase_age(&mut self) {
+        self.age += 1;
+    }
+
+    /// Computes the totals from the genes, walking every group.
+    pub(crate) fn summarize(&mut self, problem: &Problem) {
+        (self.gain, self.cost) = self.genes.iter().enumerate()
+            .map(|(group, option)| problem.option_summary(group, *option as usize))
+            .fold((0, 0), |(gain, cost), (g, c)| (gain + g, cost + c));
+    }
+
+    /// Sets `group` to `option`, updating the totals with the difference of the two options.
+    pub(crate) fn set_gene(&mut self, problem: &Problem, group: usize, option: u8) {
+        let (old_gain, old_cost) = problem.option_summary(group, self.genes[group] as usize);
+        let (gain, cost) = problem.option_summary(group, option as usize);
+        self.gain += gain - old_gain;
+        self.cost += cost - old_cost;
+        self.genes[group] = option;
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem};
+
+    #[test]
+    fn test_set_gene_keeps_totals() {
+        let problem = make_problem(20, vec![
+            vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)],
+            vec![make_item(15, 7), make_item(25, 12), make_item(45, 15)],
+        ], 2);
+        let mut chromosome = Chromosome::init_chromosome(vec![1, 3]);
+        chromosome.summarize(&problem);
+        assert_eq!((chromosome.gain, chromosome.cost), (55, 20));
+        chromosome.set_gene(&problem, 1, 0);
+        chromosome.set_gene(&problem, 0, 2);
+        assert_eq!((chromosome.gain, chromosome.cost), (20, 10));
+    }
+}
@@ -1,17 +1,56 @@
+#![allow(clippy::needless_arbitrary_self_type)]
+
+use crate::structure::constraint_handling::ConstraintHandling;
+use crate::structure::seeding_mix::SeedingMix;
+use std::fmt;
+use std::str::FromStr;
+
 pub trait Configuration {
     fn get_no_upgrade_limit(self: &Self) -> u8;
     fn get_population_size(self: &Self) -> u32;
     fn get_initial_mutation_factor(self: &Self) -> u16;
     fn get_seed(self: &Self) -> u64;
     fn is_enhanced_enabled(self: &Self) -> bool;
+    fn get_seeding_mix(&self) -> SeedingMix;
+    fn get_grasp_rcl_size(&self) -> usize;
+    fn get_seeding_file(&self) -> Option<String>;
+    fn get_gap_tolerance(&self) -> f64;
+    fn is_domain_constrained(&self) -> bool;
+    fn get_constraint_handling(&self) -> ConstraintHandling;
 }
 
+#[derive(Clone)]
 pub struct ConfigurationByGenerations {
     pub(crate) no_upgrade_limit: u8,
     pub(crate) population_size: u32,
     pub(crate) initial_mutation_factor: u16,
     pub(crate) seed: u64,
     pub(crate) enhanced_enabled: bool,
+    pub(crate) seeding_mix: SeedingMix,
+    pub(crate) grasp_rcl_size: usize,
+    pub(crate) seeding_file: Option<String>,
+    pub(crate) gap_tolerance: f64,
+    pub(crate) domain_constrained: bool,
+    pub(crate) constraint_handling: ConstraintHandling,
+}
+
+impl Default for ConfigurationByGenerations {
+    // same defaults as the command line, population size is 5m for 100 groups
+    fn default() -> Self {
+        ConfigurationByGenerations {
+            no_upgrade_limit: 128,
+            population_size: 500,
+            initial_mutation_factor: 10,
+            seed: 0,
+            enhanced_enabled: false,
+            seeding_mix: SeedingMix::default(),
+            grasp_rcl_size: 5,
+            seeding_file: None,
+            gap_tolerance: 0.0,
+            domain_constrained: true,
+            constraint_handling: ConstraintHandling::Repair,
+        }
+    }
 }
 
 impl Configuration for ConfigurationByGenerations {
@@ -32,10 +71,88 @@ impl Configuration for ConfigurationByGenerations {
     fn is_enhanced_enabled(self: &Self) -> bool {
         self.enhanced_enabled
     }
+
+    fn get_seeding_mix(&self) -> SeedingMix {
+        self.seeding_mix.clone()
+    }
+
+    fn get_grasp_rcl_size(&self) -> usize {
+        self.grasp_rcl_size
+    }
+
+    fn get_seeding_file(&self) -> Option<String> {
+        self.seeding_file.clone()
+    }
+
+    fn get_gap_tolerance(&self) -> f64 {
+        self.gap_tolerance
+    }
+
+    fn is_domain_constrained(&self) -> bool {
+        self.domain_constrained
+    }
+
+    fn get_constraint_handling(&self) -> ConstraintHandling {
+        self.constraint_handling
+    }
 }
 
+/// Population size, either an absolute number of chromosomes or a multiple of the number of groups m.
+///
+/// The crossover needs two parents, a multiple resolves to at least 2 chromosomes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopulationSize {
+    Absolute(u32),
+    PerGroup(f64),
+}
 
+impl PopulationSize {
+    pub fn resolve(&self, groups: i32) -> u32 {
+        match self {
+            PopulationSize::Absolute(size) => *size,
+            PopulationSize::PerGroup(factor) => ((factor * groups as f64).round() as u32).max(2),
+        }
+    }
+}
 
+impl From<u32> for PopulationSize {
+    fn from(size: u32) -> Self {
+        PopulationSize::Absolute(size)
+    }
+}
 
+impl fmt::Display for PopulationSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopulationSize::Absolute(size) => write!(f, "{}", size),
+            PopulationSize::PerGroup(factor) => write!(f, "{}m", factor),
+        }
+    }
+}
 
+impl FromStr for PopulationSize {
+    type Err = String;
 
+    // "500" is an absolute size, "5m" is five times the number of groups
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_suffix('m') {
+            Some(factor) => {
+                let factor = factor.trim().parse::<f64>()
+                    .map_err(|_| format!("invalid population multiple '{}', expected e.g. 5m", s))?;
+                if !factor.is_finite() || factor <= 0.0 {
+                    return Err(format!("population multiple '{}' must be positive", s));
+                }
+                Ok(PopulationSize::PerGroup(factor))
+            }
+            None => {
+                let size = s.parse::<u32>()
+                    .map_err(|_| format!("invalid population size '{}', expected e.g. 500 or 5m", s))?;
+                if size < 2 {
+                    return Err("population size must be at least 2".to_string());
+                }
+                Ok(PopulationSize::Absolute(size))
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// How the genetic algorithm deals with chromosomes exceeding the capacity.
+///
+/// Only [`ConstraintHandling::Repair`] keeps the population feasible, the penalties let the
+/// search cross the capacity boundary. The best chromosome returned is always feasible.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConstraintHandling {
+    /// Every offspring is repaired by dropping its items of worst rate.
+    #[default]
+    Repair,
+    /// Infeasible chromosomes get a fitness of 0.
+    DeathPenalty,
+    /// Gain minus the coefficient times the excess cost, the largest gain/cost ratio of the items
+    /// when no coefficient is given.
+    StaticPenalty(Option<f64>),
+    /// Gain minus a coefficient times the excess cost, the coefficient grows while few
+    /// chromosomes of the recent generations are feasible and shrinks otherwise.
+    AdaptivePenalty,
+}
+
+impl ConstraintHandling {
+    pub fn is_penalty(&self) -> bool {
+        !matches!(self, ConstraintHandling::Repair)
+    }
+}
+
+impl fmt::Display for ConstraintHandling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintHandling::Repair => write!(f, "repair"),
+            ConstraintHandling::DeathPenalty => write!(f, "death"),
+            ConstraintHandling::StaticPenalty(None) => write!(f, "static"),
+            ConstraintHandling::StaticPenalty(Some(coefficient)) => write!(f, "static:{}", coefficient),
+            ConstraintHandling::AdaptivePenalty => write!(f, "adaptive"),
+        }
+    }
+}
+
+impl FromStr for ConstraintHandling {
+    type Err = String;
+
+    // "repair", "death", "static", "static:2.5" or "adaptive"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match s.trim().split_once(':') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (s.trim(), None),
+        };
+        match (name, value) {
+            ("repair", None) => Ok(ConstraintHandling::Repair),
+            ("death", None) => Ok(ConstraintHandling::DeathPenalty),
+            ("adaptive", None) => Ok(ConstraintHandling::AdaptivePenalty),
+            ("static", None) => Ok(ConstraintHandling::StaticPenalty(None)),
+            ("static", Some(value)) => match value.parse::<f64>() {
+                Ok(coefficient) if coefficient.is_finite() && coefficient > 0.0 => Ok(ConstraintHandling::StaticPenalty(Some(coefficient))),
+                _ => Err(format!("static penalty coefficient '{}' must be a positive number", value)),
+            },
+            _ => Err(format!("unknown constraint handling '{}', expected repair, death, static[:coefficient] or adaptive", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_constraint_handling() {
+        assert_eq!("repair".parse(), Ok(ConstraintHandling::Repair));
+        assert_eq!(" death".parse(), Ok(ConstraintHandling::DeathPenalty));
+        assert_eq!("static".parse(), Ok(ConstraintHandling::StaticPenalty(None)));
+        assert_eq!("static:2.5".parse(), Ok(ConstraintHandling::StaticPenalty(Some(2.5))));
+        assert_eq!("adaptive".parse(), Ok(ConstraintHandling::AdaptivePenalty));
+        assert!("static:-1".parse::<ConstraintHandling>().is_err());
+        assert!("death:2".parse::<ConstraintHandling>().is_err());
+        assert_eq!(ConstraintHandling::StaticPenalty(Some(2.5)).to_string(), "static:2.5");
+    }
+}
@@ -1,5 +1,5 @@
-#[derive(Clone)]
-pub(crate) struct UBFixResult {
+#[derive(Debug, Clone)]
+pub struct UBFixResult {
     pub(crate) f_1: Vec<(usize, usize)>,
     pub(crate) x_best: Vec<[f64; 3]>,
     pub(crate) v_best: f64,
@@ -14,6 +14,22 @@ impl UBFixResult {
         }
     }
 
+    /// Groups fixed by the UB-fix, as (group, item) pairs.
+    pub fn f_1(&self) -> &[(usize, usize)] {
+        &self.f_1
+    }
+
+    /// Choice of every group in the best solution found while fixing, empty when the UB-fix has
+    /// not been run.
+    pub fn choices(&self) -> Vec<usize> {
+        self.x_best.iter().map(|x_i| x_i.iter().position(|v| *v == 1.0).map_or(0, |k| k + 1)).collect()
+    }
+
+    /// Best feasible value found while fixing.
+    pub fn v_best(&self) -> f64 {
+        self.v_best
+    }
+
     pub fn empty() -> Self {
         UBFixResult {
             f_1: vec![],
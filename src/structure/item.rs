@@ -5,3 +5,21 @@ pub struct Item {
     pub(crate) rate: f64,
 }
 
+impl Item {
+    pub fn new(gain: i64, cost: i64) -> Self {
+        crate::structure::make_item(gain, cost)
+    }
+
+    pub fn gain(&self) -> i64 {
+        self.gain
+    }
+
+    pub fn cost(&self) -> i64 {
+        self.cost
+    }
+
+    /// Cost over gain ratio, lower is better.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct ItemPreprocessing {
     pub(crate) cost: i64,
     pub(crate) gain: i64,
@@ -0,0 +1,55 @@
+#[derive(Debug, Clone)]
+pub struct LagrangianResult {
+    pub(crate) bound: f64,
+    pub(crate) multiplier: f64,
+    pub(crate) choices: Vec<usize>,
+    pub(crate) value: i64,
+    pub(crate) iterations: u32,
+}
+
+impl LagrangianResult {
+    pub fn new(bound: f64, multiplier: f64, choices: Vec<usize>, value: i64, iterations: u32) -> Self {
+        LagrangianResult {
+            bound,
+            multiplier,
+            choices,
+            value,
+            iterations,
+        }
+    }
+
+    /// Smallest value of the Lagrangian function met by the subgradient optimization.
+    pub fn bound(&self) -> f64 {
+        self.bound
+    }
+
+    /// Multiplier of the capacity constraint giving the bound.
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    /// Choice of every group in the best solution of the Lagrangian heuristic, empty when the
+    /// relaxation has not been run.
+    pub fn choices(&self) -> &[usize] {
+        &self.choices
+    }
+
+    /// Value of the best solution of the Lagrangian heuristic.
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub fn empty() -> Self {
+        LagrangianResult {
+            bound: 0.0,
+            multiplier: 0.0,
+            choices: vec![],
+            value: 0,
+            iterations: 0,
+        }
+    }
+}
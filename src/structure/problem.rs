@@ -1,9 +1,72 @@
 use crate::structure::item::Item;
 
+// options of a group in the flat arrays, the empty one first
+pub(crate) const OPTIONS: usize = 4;
+
+/// A D{0-1}KP instance: a knapsack capacity and `size` groups of three items.
 #[derive(Debug)]
 #[derive(Clone)]
 pub struct Problem {
     pub(crate) capacity: u32,
     pub(crate) data: Vec<Vec<Item>>,
     pub(crate) size: i32,
+    // gains, costs and rates of the options, group after group, read by the evaluation
+    pub(crate) gains: Vec<i64>,
+    pub(crate) costs: Vec<i64>,
+    pub(crate) rates: Vec<f64>,
+}
+
+impl Problem {
+    /// Builds a problem from its capacity and groups, every group holds the first item, the
+    /// second item and their discounted combination, in this order.
+    ///
+    /// ```
+    /// use discounted_zeroone_knapsack::{Item, Problem};
+    ///
+    /// let problem = Problem::new(50, vec![
+    ///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+    /// ]);
+    /// assert_eq!(problem.size(), 1);
+    /// ```
+    pub fn new(capacity: u32, data: Vec<Vec<Item>>) -> Self {
+        let size = data.len() as i32;
+        Problem::with_size(capacity, data, size)
+    }
+
+    pub(crate) fn with_size(capacity: u32, data: Vec<Vec<Item>>, size: i32) -> Self {
+        let mut gains = vec![0; data.len() * OPTIONS];
+        let mut costs = vec![0; data.len() * OPTIONS];
+        let mut rates = vec![0.0; data.len() * OPTIONS];
+        for (group, items) in data.iter().enumerate() {
+            for (k, item) in items.iter().take(OPTIONS - 1).enumerate() {
+                let index = group * OPTIONS + k + 1;
+                (gains[index], costs[index], rates[index]) = (item.gain, item.cost, item.rate);
+            }
+        }
+        Problem { capacity, data, size, gains, costs, rates }
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Number of groups.
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    pub fn groups(&self) -> &[Vec<Item>] {
+        &self.data
+    }
+
+    /// Gain and cost of `option` in `group`, 0 being the empty option.
+    pub(crate) fn option_summary(&self, group: usize, option: usize) -> (i64, i64) {
+        let index = group * OPTIONS + option;
+        (self.gains[index], self.costs[index])
+    }
+
+    /// Cost over gain rate of `option` in `group`, 0 for the empty option.
+    pub(crate) fn option_rate(&self, group: usize, option: usize) -> f64 {
+        self.rates[group * OPTIONS + option]
+    }
 }
@@ -0,0 +1,40 @@
+#[derive(Debug, Clone)]
+pub struct ReducedCostResult {
+    pub(crate) f_0: Vec<(usize, usize)>,
+    pub(crate) f_1: Vec<(usize, usize)>,
+    pub(crate) multiplier: f64,
+}
+
+impl ReducedCostResult {
+    pub fn new(f_0: Vec<(usize, usize)>, f_1: Vec<(usize, usize)>, multiplier: f64) -> Self {
+        ReducedCostResult {
+            f_0,
+            f_1,
+            multiplier,
+        }
+    }
+
+    /// Items no solution better than the UB-fix one takes, as (group, item) pairs.
+    pub fn f_0(&self) -> &[(usize, usize)] {
+        &self.f_0
+    }
+
+    /// Groups taking the same item in every solution better than the UB-fix one, as
+    /// (group, item) pairs.
+    pub fn f_1(&self) -> &[(usize, usize)] {
+        &self.f_1
+    }
+
+    /// Multiplier of the capacity constraint the reduced costs are computed with.
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    pub fn empty() -> Self {
+        ReducedCostResult {
+            f_0: vec![],
+            f_1: vec![],
+            multiplier: 0.0,
+        }
+    }
+}
@@ -1,8 +1,40 @@
 use crate::structure::item_preprocessing::ItemPreprocessing;
+use serde::Serialize;
+use std::fmt;
 
-#[derive(Clone)]
+/// Dominance rule of the LP relaxation that removed an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DominanceRule {
+    /// The first item is dominated by the second one.
+    #[serde(rename = "eq13")]
+    Eq13,
+    /// The first item is dominated by the third one.
+    #[serde(rename = "eq14")]
+    Eq14,
+    /// The second item lies below the segment from the first item to the third one.
+    #[serde(rename = "eq15")]
+    Eq15,
+    /// The second item is dominated by the third one.
+    #[serde(rename = "eq16")]
+    Eq16,
+}
+
+impl fmt::Display for DominanceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let equation = match self {
+            DominanceRule::Eq13 => 13,
+            DominanceRule::Eq14 => 14,
+            DominanceRule::Eq15 => 15,
+            DominanceRule::Eq16 => 16,
+        };
+        f.pad(&format!("eq{}", equation))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct LPRelaxationResult {
     pub(crate) f_0: Vec<(usize, usize)>,
+    pub(crate) rules: Vec<DominanceRule>,
     pub(crate) x_up: Vec<[f64; 3]>,
     pub(crate) x: Vec<[f64; 3]>,
     pub(crate) v_up: f64,
@@ -11,9 +43,10 @@ pub struct LPRelaxationResult {
 }
 
 impl LPRelaxationResult {
-    pub fn new(f_0: Vec<(usize, usize)>, x_up: Vec<[f64; 3]>, x: Vec<[f64; 3]>, v_up: f64, v_low: f64, relaxed: Vec<[ItemPreprocessing; 3]>) -> Self {
+    pub(crate) fn new(f_0: Vec<(usize, usize)>, x_up: Vec<[f64; 3]>, x: Vec<[f64; 3]>, v_up: f64, v_low: f64, relaxed: Vec<[ItemPreprocessing; 3]>) -> Self {
         LPRelaxationResult {
             f_0,
+            rules: vec![],
             x_up,
             x,
             v_up,
@@ -22,9 +55,30 @@ impl LPRelaxationResult {
         }
     }
 
+    /// LP-dominated items, as (group, item) pairs.
+    pub fn f_0(&self) -> &[(usize, usize)] {
+        &self.f_0
+    }
+
+    /// Rule that removed every item of [`LPRelaxationResult::f_0`], in the same order.
+    pub fn rules(&self) -> &[DominanceRule] {
+        &self.rules
+    }
+
+    /// Upper bound given by the LP relaxation.
+    pub fn v_up(&self) -> f64 {
+        self.v_up
+    }
+
+    /// Value of the feasible solution built by the greedy.
+    pub fn v_low(&self) -> f64 {
+        self.v_low
+    }
+
     pub fn empty() -> Self {
         LPRelaxationResult {
             f_0: vec![],
+            rules: vec![],
             x_up: vec![],
             x: vec![],
             v_up: 0.0,
@@ -0,0 +1,262 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Strategy used to generate part of the initial population.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedingStrategy {
+    UbFixBest,
+    F0,
+    F1,
+    F0AndF1,
+    Random,
+    Greedy,
+    Grasp,
+    Opposition,
+    File,
+    Lagrangian,
+}
+
+impl SeedingStrategy {
+    /// All strategies, in the order used by the report columns, the Lagrangian share being the
+    /// last column of the row.
+    pub const ALL: [SeedingStrategy; 10] = [
+        SeedingStrategy::UbFixBest,
+        SeedingStrategy::F0,
+        SeedingStrategy::F1,
+        SeedingStrategy::F0AndF1,
+        SeedingStrategy::Random,
+        SeedingStrategy::Greedy,
+        SeedingStrategy::Grasp,
+        SeedingStrategy::Opposition,
+        SeedingStrategy::File,
+        SeedingStrategy::Lagrangian,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SeedingStrategy::UbFixBest => "best",
+            SeedingStrategy::F0 => "f0",
+            SeedingStrategy::F1 => "f1",
+            SeedingStrategy::F0AndF1 => "f0f1",
+            SeedingStrategy::Random => "random",
+            SeedingStrategy::Greedy => "greedy",
+            SeedingStrategy::Grasp => "grasp",
+            SeedingStrategy::Opposition => "obl",
+            SeedingStrategy::File => "file",
+            SeedingStrategy::Lagrangian => "lagrangian",
+        }
+    }
+
+    /// Strategies built on top of the preprocessing results.
+    pub fn requires_preprocessing(&self) -> bool {
+        matches!(self, SeedingStrategy::UbFixBest | SeedingStrategy::F0 | SeedingStrategy::F1 | SeedingStrategy::F0AndF1 | SeedingStrategy::Lagrangian)
+    }
+
+    fn index(&self) -> usize {
+        SeedingStrategy::ALL.iter().position(|s| s == self).unwrap()
+    }
+}
+
+impl fmt::Display for SeedingStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for SeedingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SeedingStrategy::ALL.iter()
+            .find(|strategy| strategy.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = SeedingStrategy::ALL.iter().map(|s| s.name()).collect();
+                format!("unknown seeding strategy '{}', expected one of {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Share of the initial population produced by each seeding strategy.
+///
+/// Shares are relative weights, they are normalized over their sum when the
+/// population is allocated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedingMix {
+    shares: [f64; 10],
+}
+
+/// Number of individuals actually generated by each seeding strategy.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SeedingCounts {
+    counts: [u32; 10],
+}
+
+impl SeedingMix {
+    pub fn share(&self, strategy: SeedingStrategy) -> f64 {
+        self.shares[strategy.index()]
+    }
+
+    pub fn uses(&self, strategy: SeedingStrategy) -> bool {
+        self.share(strategy) > 0.0
+    }
+
+    pub fn without(&self, strategy: SeedingStrategy) -> Self {
+        let mut mix = self.clone();
+        mix.shares[strategy.index()] = 0.0;
+        mix
+    }
+
+    pub fn without_preprocessing(&self) -> Self {
+        SeedingStrategy::ALL.iter()
+            .filter(|s| s.requires_preprocessing())
+            .fold(self.clone(), |mix, s| mix.without(*s))
+    }
+
+    // largest remainder allocation, every strategy with a positive share gets at least one individual
+    pub fn allocate(&self, population_size: u32) -> SeedingCounts {
+        let shares = self.shares;
+        let total: f64 = shares.iter().sum();
+        if total <= 0.0 || population_size == 0 {
+            return SeedingCounts::only(SeedingStrategy::Random, population_size);
+        }
+
+        let quotas: Vec<f64> = shares.iter().map(|s| s / total * population_size as f64).collect();
+        let mut counts = [0u32; 10];
+        for (count, quota) in counts.iter_mut().zip(&quotas) {
+            *count = quota.floor() as u32;
+        }
+        let mut assigned: u32 = counts.iter().sum();
+
+        let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+        by_remainder.sort_by(|a, b| (quotas[*b] - quotas[*b].floor()).total_cmp(&(quotas[*a] - quotas[*a].floor())));
+        for index in by_remainder {
+            if assigned >= population_size {
+                break;
+            }
+            if shares[index] > 0.0 {
+                counts[index] += 1;
+                assigned += 1;
+            }
+        }
+
+        for index in 0..counts.len() {
+            if shares[index] > 0.0 && counts[index] == 0 {
+                let donor = (0..counts.len()).max_by_key(|i| counts[*i]).unwrap();
+                if counts[donor] > 1 {
+                    counts[donor] -= 1;
+                    counts[index] = 1;
+                }
+            }
+        }
+
+        SeedingCounts { counts }
+    }
+}
+
+impl Default for SeedingMix {
+    fn default() -> Self {
+        "best=0.01,f0=0.04,random=0.95".parse().unwrap()
+    }
+}
+
+impl FromStr for SeedingMix {
+    type Err = String;
+
+    // format: best=0.01,f0=0.05,greedy=0.1,random=0.84, missing strategies get no share
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mix = SeedingMix { shares: [0.0; 10] };
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, value) = entry.split_once('=')
+                .ok_or_else(|| format!("invalid seeding entry '{}', expected <strategy>=<share>", entry))?;
+            let strategy = name.trim().parse::<SeedingStrategy>()?;
+            let share = value.trim().parse::<f64>()
+                .map_err(|_| format!("invalid share '{}' for seeding strategy '{}'", value, strategy))?;
+            if !share.is_finite() || share < 0.0 {
+                return Err(format!("share for seeding strategy '{}' must be a non negative number", strategy));
+            }
+            mix.shares[strategy.index()] = share;
+        }
+        if mix.shares.iter().sum::<f64>() <= 0.0 {
+            return Err("at least one seeding strategy must have a positive share".to_string());
+        }
+        Ok(mix)
+    }
+}
+
+impl SeedingCounts {
+    pub fn only(strategy: SeedingStrategy, count: u32) -> Self {
+        let mut counts = SeedingCounts::default();
+        counts.counts[strategy.index()] = count;
+        counts
+    }
+
+    pub fn get(&self, strategy: SeedingStrategy) -> u32 {
+        self.counts[strategy.index()]
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seeding_mix() {
+        let mix: SeedingMix = "best=0.1, f0=0.2,f1=0.3,f0f1=0.4,random=0,grasp=0.5".parse().unwrap();
+        assert_eq!(mix.share(SeedingStrategy::UbFixBest), 0.1);
+        assert_eq!(mix.share(SeedingStrategy::F0), 0.2);
+        assert_eq!(mix.share(SeedingStrategy::F1), 0.3);
+        assert_eq!(mix.share(SeedingStrategy::F0AndF1), 0.4);
+        assert_eq!(mix.share(SeedingStrategy::Random), 0.0);
+        assert_eq!(mix.share(SeedingStrategy::Grasp), 0.5);
+        assert!(!mix.uses(SeedingStrategy::File));
+    }
+
+    #[test]
+    fn test_parse_seeding_mix_rejects_invalid_entries() {
+        assert!("best=0.1,unknown=0.9".parse::<SeedingMix>().is_err());
+        assert!("f0=-1".parse::<SeedingMix>().is_err());
+        assert!("random=0".parse::<SeedingMix>().is_err());
+        assert!("random".parse::<SeedingMix>().is_err());
+    }
+
+    #[test]
+    fn test_allocate_fills_whole_population() {
+        let counts = SeedingMix::default().allocate(103);
+        assert_eq!(counts.total(), 103);
+        assert_eq!(counts.get(SeedingStrategy::UbFixBest), 1);
+        assert_eq!(counts.get(SeedingStrategy::F0), 4);
+        assert_eq!(counts.get(SeedingStrategy::F1) + counts.get(SeedingStrategy::F0AndF1), 0);
+    }
+
+    #[test]
+    fn test_allocate_gives_one_individual_to_every_used_strategy() {
+        let mix: SeedingMix = "best=0.01,f0=0.05,f1=0.025,f0f1=0.025,random=0.89".parse().unwrap();
+        let counts = mix.allocate(10);
+        assert_eq!(counts.total(), 10);
+        for strategy in [SeedingStrategy::UbFixBest, SeedingStrategy::F0, SeedingStrategy::F1, SeedingStrategy::F0AndF1] {
+            assert_eq!(counts.get(strategy), 1);
+        }
+    }
+
+    #[test]
+    fn test_allocate_random_only() {
+        let mix: SeedingMix = "random=1".parse().unwrap();
+        let counts = mix.allocate(20);
+        assert_eq!(counts, SeedingCounts::only(SeedingStrategy::Random, 20));
+    }
+
+    #[test]
+    fn test_without_preprocessing_keeps_standalone_strategies() {
+        let mix: SeedingMix = "best=0.1,f0=0.1,greedy=0.2,random=0.6".parse().unwrap();
+        let counts = mix.without_preprocessing().allocate(8);
+        assert_eq!(counts.get(SeedingStrategy::UbFixBest), 0);
+        assert_eq!(counts.get(SeedingStrategy::F0), 0);
+        assert_eq!(counts.get(SeedingStrategy::Greedy), 2);
+        assert_eq!(counts.get(SeedingStrategy::Random), 6);
+    }
+}
@@ -1,20 +1,61 @@
 use crate::structure::item::Item;
+use crate::structure::problem::Problem;
 
 #[derive(Debug)]
 pub struct Solution {
     pub(crate) cost: i64,
     pub(crate) data: Vec<Item>,
+    pub(crate) choices: Vec<usize>,
     pub(crate) fitness: i64,
     pub(crate) generations: u32,
 }
 
 impl Solution {
-    pub fn make_solution(data: Vec<Item>, fitness: i64, cost: i64, generations: u32) -> Solution {
+    pub fn make_solution(data: Vec<Item>, choices: Vec<usize>, fitness: i64, cost: i64, generations: u32) -> Solution {
         Solution {
             cost,
             data,
+            choices,
             fitness,
             generations,
         }
     }
+
+    /// Solution taking the `choices` of `problem`, `0` leaving a group empty and `k` taking its
+    /// k-th item.
+    pub fn from_choices(problem: &Problem, choices: Vec<usize>, generations: u32) -> Solution {
+        let mut data = Vec::new();
+        let (mut gain, mut cost) = (0, 0);
+        for (i, choice) in choices.iter().enumerate() {
+            if *choice != 0 {
+                let item = &problem.data[i][choice - 1];
+                data.push(item.clone());
+                gain += item.gain;
+                cost += item.cost;
+            }
+        }
+        Solution::make_solution(data, choices, gain, cost, generations)
+    }
+
+    pub fn cost(&self) -> i64 {
+        self.cost
+    }
+
+    /// Items taken, in group order.
+    pub fn items(&self) -> &[Item] {
+        &self.data
+    }
+
+    /// Choice for every group, `0` when the group is empty and `k` when its k-th item is taken.
+    pub fn choices(&self) -> &[usize] {
+        &self.choices
+    }
+
+    pub fn fitness(&self) -> i64 {
+        self.fitness
+    }
+
+    pub fn generations(&self) -> u32 {
+        self.generations
+    }
 }
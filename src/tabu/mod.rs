@@ -0,0 +1,355 @@
+use crate::error::SolverError;
+use crate::genetic::operators;
+use crate::solver::Cancellation;
+use crate::structure::problem::Problem;
+use crate::structure::solution::Solution;
+use crate::utils;
+use log::{debug, info};
+use rand::Rng;
+use std::sync::Arc;
+
+/// Parameters of the tabu search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabuSettings {
+    pub(crate) tenure: usize,
+    pub(crate) candidates: usize,
+    pub(crate) diversification: f64,
+}
+
+impl TabuSettings {
+    pub fn new() -> Self {
+        TabuSettings::default()
+    }
+
+    /// Iterations during which a group can not go back to the option it just left.
+    pub fn tenure(mut self, tenure: usize) -> Self {
+        self.tenure = tenure;
+        self
+    }
+
+    /// Moves sampled at every iteration instead of the whole neighbourhood of 3m moves.
+    pub fn candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Weight of the frequency penalty applied to non-improving moves, 0 disables diversification.
+    pub fn diversification(mut self, diversification: f64) -> Self {
+        self.diversification = diversification;
+        self
+    }
+
+    fn validate(&self) -> Result<(), SolverError> {
+        if self.candidates == 0 {
+            return Err(SolverError::InvalidTabuSettings("candidate list size must be greater than 0".to_string()));
+        }
+        if !(self.diversification >= 0.0 && self.diversification.is_finite()) {
+            return Err(SolverError::InvalidTabuSettings(format!("diversification weight {} must not be negative", self.diversification)));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TabuSettings {
+    fn default() -> Self {
+        TabuSettings {
+            tenure: 10,
+            candidates: 100,
+            diversification: 0.5,
+        }
+    }
+}
+
+/// Tabu search over the choice of one group at a time.
+///
+/// Every iteration samples a candidate list of (group, option) moves, infeasible ones being
+/// repaired with the rule of the genetic algorithm, and applies the best of them. Leaving option
+/// `k` of a group, by the move or by its repair, makes going back to it tabu for `tenure`
+/// iterations, unless the move improves the best solution. Non-improving moves are penalized by
+/// how often the group has taken the option, steering the search to unexplored choices. The
+/// search stops after `stagnation_limit` epochs of m iterations without improvement.
+///
+/// ```
+/// use discounted_zeroone_knapsack::{Item, Problem, TabuSearch, TabuSettings};
+///
+/// let problem = Problem::new(20, vec![
+///     vec![Item::new(10, 5), Item::new(20, 10), Item::new(30, 14)],
+///     vec![Item::new(15, 7), Item::new(25, 12), Item::new(45, 15)],
+/// ]);
+/// let (solution, _) = TabuSearch::new(problem).settings(TabuSettings::new().tenure(2)).solve().unwrap();
+/// assert_eq!(solution.fitness(), 55);
+/// ```
+pub struct TabuSearch {
+    problem: Arc<Problem>,
+    settings: TabuSettings,
+    seed: u64,
+    stagnation_limit: u8,
+    cancellation: Cancellation,
+}
+
+#[derive(Debug, Clone)]
+pub struct TabuStatistics {
+    pub(crate) iterations: u64,
+    pub(crate) aspirations: u64,
+}
+
+impl TabuStatistics {
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    /// Tabu moves accepted because they improved the best solution.
+    pub fn aspirations(&self) -> u64 {
+        self.aspirations
+    }
+}
+
+// best move of a candidate list, `genes` is set when the move needed a repair
+struct Candidate {
+    group: usize,
+    option: usize,
+    gain: i64,
+    cost: i64,
+    score: f64,
+    tabu: bool,
+    genes: Option<Vec<usize>>,
+}
+
+// current and best solutions with the tabu and frequency memories
+struct SearchState {
+    genes: Vec<usize>,
+    gain: i64,
+    cost: i64,
+    best_genes: Vec<usize>,
+    best: i64,
+    mean_gain: f64,
+    tabu_until: Vec<[u64; 4]>,
+    frequency: Vec<[u64; 4]>,
+    statistics: TabuStatistics,
+}
+
+// the groups a repaired move changed can not go back to their option either
+fn forbid_left_options(tabu_until: &mut [[u64; 4]], genes: &[usize], next: &[usize], until: u64) {
+    for (group, (old, new)) in genes.iter().zip(next).enumerate() {
+        if old != new {
+            tabu_until[group][*old] = until;
+        }
+    }
+}
+
+impl TabuSearch {
+    pub fn new(problem: impl Into<Arc<Problem>>) -> Self {
+        TabuSearch {
+            problem: problem.into(),
+            settings: TabuSettings::default(),
+            seed: 0,
+            stagnation_limit: 128,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    pub fn settings(mut self, settings: TabuSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Epochs of m iterations without improvement before the search stops.
+    pub fn stagnation_limit(mut self, stagnation_limit: u8) -> Self {
+        self.stagnation_limit = stagnation_limit;
+        self
+    }
+
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    fn option_value(&self, group: usize, option: usize) -> (i64, i64) {
+        match option {
+            0 => (0, 0),
+            k => (self.problem.data[group][k - 1].gain, self.problem.data[group][k - 1].cost),
+        }
+    }
+
+    fn start(&self, mut genes: Vec<usize>) -> SearchState {
+        let m = genes.len();
+        operators::repair(&self.problem, &mut genes);
+        let (gain, cost) = operators::summary(&self.problem, &genes);
+        SearchState {
+            best_genes: genes.clone(),
+            best: gain,
+            genes,
+            gain,
+            cost,
+            mean_gain: self.problem.data.iter().flatten().map(|item| item.gain as f64).sum::<f64>() / (3 * m) as f64,
+            tabu_until: vec![[0u64; 4]; m],
+            frequency: vec![[0u64; 4]; m],
+            statistics: TabuStatistics { iterations: 0, aspirations: 0 },
+        }
+    }
+
+    // best admissible move of the candidate list
+    fn choose(&self, state: &SearchState, moves: impl Iterator<Item = (usize, usize)>) -> Option<Candidate> {
+        let capacity = self.problem.capacity as i64;
+        let iteration = state.statistics.iterations;
+        let mut chosen: Option<Candidate> = None;
+        for (group, option) in moves {
+            if option == state.genes[group] {
+                continue;
+            }
+            let (old_gain, old_cost) = self.option_value(group, state.genes[group]);
+            let (new_gain, new_cost) = self.option_value(group, option);
+            let mut candidate = Candidate {
+                group,
+                option,
+                gain: state.gain - old_gain + new_gain,
+                cost: state.cost - old_cost + new_cost,
+                score: 0.0,
+                tabu: state.tabu_until[group][option] > iteration,
+                genes: None,
+            };
+            if candidate.cost > capacity {
+                let mut repaired = state.genes.clone();
+                repaired[group] = option;
+                operators::repair(&self.problem, &mut repaired);
+                (candidate.gain, candidate.cost) = operators::summary(&self.problem, &repaired);
+                candidate.genes = Some(repaired);
+            }
+            // aspiration by objective
+            if candidate.tabu && candidate.gain <= state.best {
+                continue;
+            }
+            candidate.score = candidate.gain as f64;
+            if candidate.gain <= state.gain {
+                // visits per epoch of m iterations
+                let visits = state.frequency[group][option] as f64 / (iteration / state.genes.len() as u64 + 1) as f64;
+                candidate.score -= self.settings.diversification * state.mean_gain * visits;
+            }
+            if chosen.as_ref().is_none_or(|c| candidate.score > c.score) {
+                chosen = Some(candidate);
+            }
+        }
+        chosen
+    }
+
+    // moves to the candidate, returns whether it improved the best solution
+    fn apply(&self, state: &mut SearchState, candidate: Candidate) -> bool {
+        let until = state.statistics.iterations + self.settings.tenure as u64;
+        if candidate.tabu {
+            state.statistics.aspirations += 1;
+        }
+        match candidate.genes {
+            Some(repaired) => {
+                forbid_left_options(&mut state.tabu_until, &state.genes, &repaired, until);
+                state.genes = repaired;
+            }
+            None => {
+                state.tabu_until[candidate.group][state.genes[candidate.group]] = until;
+                state.genes[candidate.group] = candidate.option;
+            }
+        }
+        (state.gain, state.cost) = (candidate.gain, candidate.cost);
+        state.frequency[candidate.group][candidate.option] += 1;
+        if state.gain > state.best {
+            state.best = state.gain;
+            state.best_genes = state.genes.clone();
+            return true;
+        }
+        false
+    }
+
+    pub fn solve(&self) -> Result<(Solution, TabuStatistics), SolverError> {
+        self.settings.validate()?;
+        if self.problem.data.is_empty() {
+            return Err(SolverError::EmptyProblem);
+        }
+        info!("Running tabu search for knapsack capacity: {}, selection size: {} ", self.problem.capacity, self.problem.size);
+
+        let m = self.problem.data.len();
+        let mut rng = utils::make_rng(self.seed);
+        let mut state = self.start((0..m).map(|_| rng.gen_range(0..4)).collect());
+        let (mut epochs, mut stagnant): (u32, u32) = (0, 0);
+        let mut improved = false;
+
+        while stagnant <= self.stagnation_limit as u32 && !self.cancellation.is_cancelled() {
+            let moves = (0..self.settings.candidates).map(|_| (rng.gen_range(0..m), rng.gen_range(0..4)));
+            if let Some(candidate) = self.choose(&state, moves) {
+                improved |= self.apply(&mut state, candidate);
+            }
+
+            state.statistics.iterations += 1;
+            if state.statistics.iterations % m as u64 == 0 {
+                epochs += 1;
+                stagnant = if improved { 0 } else { stagnant + 1 };
+                improved = false;
+                info!("Tabu search epoch: {} current: {} best: {}", epochs, state.gain, state.best);
+            }
+        }
+
+        let statistics = state.statistics;
+        debug!("Tabu search ran {} iterations with {} aspirations", statistics.iterations, statistics.aspirations);
+        Ok((Solution::from_choices(&self.problem, state.best_genes, epochs), statistics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{make_item, make_problem, make_thirty_groups};
+
+    #[test]
+    fn test_no_aspiration_without_tenure() {
+        let (solution, statistics) = TabuSearch::new(make_thirty_groups()).settings(TabuSettings::new().tenure(0)).seed(2).solve().unwrap();
+        assert!(solution.cost() <= 300);
+        assert_eq!(statistics.aspirations(), 0);
+    }
+
+    #[test]
+    fn test_tabu_move_improving_the_best_is_aspirated() {
+        let group = vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)];
+        let search = TabuSearch::new(make_problem(100, vec![group.clone(), group], 2));
+        let mut state = search.start(vec![0, 0]);
+        state.tabu_until[0][3] = 10;
+        state.best = 30;
+        assert!(search.choose(&state, [(0, 3)].into_iter()).is_none());
+
+        state.best = 0;
+        let candidate = search.choose(&state, [(0, 3)].into_iter()).unwrap();
+        assert!(search.apply(&mut state, candidate));
+        assert_eq!(state.statistics.aspirations(), 1);
+        assert_eq!(state.genes, vec![3, 0]);
+    }
+
+    #[test]
+    fn test_diversification_penalizes_frequent_options() {
+        let group = vec![make_item(10, 5), make_item(20, 10), make_item(30, 14)];
+        let problem = make_problem(100, vec![group.clone(), group], 2);
+        let moves = [(0, 1), (0, 2)];
+        let chosen_option = |diversification| {
+            let search = TabuSearch::new(problem.clone()).settings(TabuSettings::new().diversification(diversification));
+            let mut state = search.start(vec![3, 3]);
+            state.frequency[0][2] = 5;
+            search.choose(&state, moves.into_iter()).unwrap().option
+        };
+        assert_eq!(chosen_option(0.0), 2);
+        assert_eq!(chosen_option(1.0), 1);
+    }
+
+    #[test]
+    fn test_repaired_groups_become_tabu() {
+        let mut tabu_until = vec![[0u64; 4]; 3];
+        forbid_left_options(&mut tabu_until, &[0, 1, 2], &[3, 1, 0], 5);
+        assert_eq!(tabu_until, vec![[5, 0, 0, 0], [0, 0, 0, 0], [0, 0, 5, 0]]);
+    }
+
+    #[test]
+    fn test_solve_rejects_empty_candidate_list() {
+        let result = TabuSearch::new(make_thirty_groups()).settings(TabuSettings::new().candidates(0)).solve();
+        assert!(matches!(result, Err(SolverError::InvalidTabuSettings(_))));
+    }
+}